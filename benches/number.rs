@@ -0,0 +1,109 @@
+#![allow(missing_docs)]
+
+use std::fmt::Write;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use macro_toolset::string::{NumStr, PushAnyT};
+
+fn bench_number(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Number");
+
+    macro_rules! bench_number {
+        ($($name:literal: $num:expr),+) => {
+            $(
+                let num = $num;
+
+                group.bench_function(concat!("std/", $name), |b| {
+                    let mut string = String::with_capacity(64);
+
+                    b.iter(|| {
+                        string.clear();
+                        write!(string, "{}", std::hint::black_box(num)).unwrap();
+                        std::hint::black_box(&string);
+                    });
+                });
+
+                group.bench_function(concat!("itoa/", $name), |b| {
+                    let mut string = String::with_capacity(64);
+
+                    b.iter(|| {
+                        string.clear();
+                        string.push_str(itoa::Buffer::new().format(std::hint::black_box(num)));
+                        std::hint::black_box(&string);
+                    });
+                });
+
+                group.bench_function(concat!("string/number/", $name), |b| {
+                    let mut string = String::with_capacity(64);
+
+                    b.iter(|| {
+                        string.clear();
+                        string.push_any(std::hint::black_box(num));
+                        std::hint::black_box(&string);
+                    });
+                });
+            )+
+        };
+    }
+
+    bench_number!(
+        "u8": 123_u8,
+        "u32": 123_456_u32,
+        "u64": 12_345_678_901_234_u64,
+        "u64-max": u64::MAX,
+        "i64-min": i64::MIN,
+        "u128-max": u128::MAX
+    );
+
+    macro_rules! bench_float {
+        ($($name:literal: $num:expr),+) => {
+            $(
+                let num = $num;
+
+                group.bench_function(concat!("std/", $name), |b| {
+                    let mut string = String::with_capacity(64);
+
+                    b.iter(|| {
+                        string.clear();
+                        write!(string, "{}", std::hint::black_box(num)).unwrap();
+                        std::hint::black_box(&string);
+                    });
+                });
+
+                group.bench_function(concat!("string/number/", $name), |b| {
+                    let mut string = String::with_capacity(64);
+
+                    b.iter(|| {
+                        string.clear();
+                        string.push_any(std::hint::black_box(num));
+                        std::hint::black_box(&string);
+                    });
+                });
+            )+
+        };
+    }
+
+    bench_float!(
+        "f32": 0.1_f32,
+        "f64": 1234.5678_f64,
+        "f64-long": 0.1_f64 + 0.2,
+        "f64-small": 1.5e-7_f64
+    );
+
+    group.bench_function("string/number/u64-padded", |b| {
+        let mut string = String::with_capacity(64);
+
+        b.iter(|| {
+            string.clear();
+            string.push_any(
+                NumStr::new_default(std::hint::black_box(12345_u64)).set_minimum_len::<20>(),
+            );
+            std::hint::black_box(&string);
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_number);
+criterion_main!(benches);
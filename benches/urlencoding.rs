@@ -1,7 +1,35 @@
 #![allow(missing_docs)]
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use macro_toolset::{string::StringExtT, urlencoding_str};
+use macro_toolset::{
+    string::{PushAnyT, StringExtT, StringT},
+    urlencoding_str,
+};
+
+/// The previous implementation, rendering the inner value into an
+/// intermediate `Vec` first then encoding byte by byte.
+fn encode_with_intermediate_vec(data: impl StringT) -> String {
+    let mut buf = Vec::with_capacity(64);
+    data.encode_to_buf(&mut buf);
+
+    let mut string = Vec::with_capacity(buf.len() | 15);
+    buf.into_iter().for_each(|byte| {
+        if matches!(byte, b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' | b'-' | b'.' | b'_' | b'~') {
+            string.push(byte);
+        } else {
+            string.extend_from_slice(&[b'%', to_hex_digit(byte >> 4), to_hex_digit(byte & 15)]);
+        }
+    });
+
+    String::from_utf8(string).unwrap()
+}
+
+const fn to_hex_digit(digit: u8) -> u8 {
+    match digit {
+        0..=9 => b'0' + digit,
+        10..=255 => b'A' - 10 + digit,
+    }
+}
 
 fn bench_urlencoding(c: &mut Criterion) {
     let mut group = c.benchmark_group("UrlEncoding");
@@ -36,7 +64,45 @@ fn bench_urlencoding(c: &mut Criterion) {
             )
         });
     });
+
+    group.finish();
+}
+
+fn bench_urlencoding_long(c: &mut Criterion) {
+    let mut group = c.benchmark_group("UrlEncodingLong");
+
+    // Long ASCII runs, with few bytes to be encoded.
+    let ascii = "abcdefghijklmnopqrstuvwxyz0123456789-".repeat(32) + "/?";
+    // Mostly bytes to be encoded.
+    let mixed = "你好, 世界! a=b&c=d ".repeat(32);
+
+    for (name, data) in [("ascii", &ascii), ("mixed", &mixed)] {
+        group.bench_function(format!("urlencoding/encode/{name}"), |b| {
+            b.iter(|| std::hint::black_box(urlencoding::encode(data)));
+        });
+
+        group.bench_function(format!("intermediate-vec/encode/{name}"), |b| {
+            b.iter(|| std::hint::black_box(encode_with_intermediate_vec(data.as_str())));
+        });
+
+        group.bench_function(format!("string/urlencoding/encode/{name}"), |b| {
+            b.iter(|| std::hint::black_box(urlencoding_str!(E: data.as_str()).to_string_ext()));
+        });
+
+        // Reusing the buffer, the inner value is encoded in place.
+        group.bench_function(format!("string/urlencoding/encode-reuse/{name}"), |b| {
+            let mut string = String::with_capacity(data.len() * 3);
+
+            b.iter(|| {
+                string.clear();
+                string.push_any(urlencoding_str!(E: data.as_str()));
+                std::hint::black_box(&string);
+            });
+        });
+    }
+
+    group.finish();
 }
 
-criterion_group!(benches, bench_urlencoding);
+criterion_group!(benches, bench_urlencoding, bench_urlencoding_long);
 criterion_main!(benches);
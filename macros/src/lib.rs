@@ -0,0 +1,363 @@
+//! Procedural macros for `macro-toolset`.
+//!
+//! Do not use this crate directly, use the re-exports in `macro-toolset`
+//! instead.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Expr, Ident, LitStr, Token,
+};
+
+#[doc(hidden)]
+#[proc_macro]
+/// See `macro_toolset::str_template!`.
+///
+/// Input: `$crate; "template", name = expr, ...`
+pub fn str_template(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as TemplateInput);
+
+    input
+        .expand()
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The parsed input of [`str_template!`].
+struct TemplateInput {
+    /// Path to the `macro-toolset` crate, i.e. `$crate`.
+    krate: TokenStream2,
+    template: LitStr,
+    args: Vec<(Ident, Expr)>,
+}
+
+impl Parse for TemplateInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut krate = TokenStream2::new();
+        while !input.peek(Token![;]) {
+            krate.extend([input.parse::<TokenTree>()?]);
+        }
+        input.parse::<Token![;]>()?;
+
+        let template = input.parse()?;
+
+        let mut args: Vec<(Ident, Expr)> = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let expr = input.parse()?;
+
+            if args.iter().any(|(arg, _)| *arg == name) {
+                return Err(Error::new(name.span(), "duplicate argument"));
+            }
+
+            args.push((name, expr));
+        }
+
+        Ok(Self {
+            krate,
+            template,
+            args,
+        })
+    }
+}
+
+impl TemplateInput {
+    fn expand(self) -> syn::Result<TokenStream2> {
+        let Self {
+            krate,
+            template,
+            args,
+        } = self;
+
+        let segments =
+            parse_template(&template.value()).map_err(|e| Error::new(template.span(), e))?;
+
+        // How many times each argument is used, the value will be cloned
+        // except for the last time.
+        let mut remaining_uses = vec![0usize; args.len()];
+        for segment in &segments {
+            if let Segment::Placeholder { name, .. } = segment {
+                if let Some(idx) = args.iter().position(|(arg, _)| arg == name) {
+                    remaining_uses[idx] += 1;
+                }
+            }
+        }
+
+        if let Some(idx) = remaining_uses.iter().position(|&uses| uses == 0) {
+            return Err(Error::new(args[idx].0.span(), "named argument never used"));
+        }
+
+        let bindings = args
+            .iter()
+            .map(|(name, _)| format_ident!("__str_template_{}", name, span = Span::mixed_site()))
+            .collect::<Vec<_>>();
+
+        let parts = segments
+            .into_iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => quote! { #literal },
+                Segment::Placeholder { name, spec } => {
+                    let value = match args.iter().position(|(arg, _)| *arg == name) {
+                        Some(idx) => {
+                            let binding = &bindings[idx];
+
+                            remaining_uses[idx] -= 1;
+
+                            if remaining_uses[idx] > 0 {
+                                quote! { ::core::clone::Clone::clone(&#binding) }
+                            } else {
+                                quote! { #binding }
+                            }
+                        }
+                        None => {
+                            // Capture the variable in scope, numbers by value while others
+                            // by reference.
+                            let ident = Ident::new(&name, template.span());
+
+                            if spec.is_number() {
+                                quote! { #ident }
+                            } else {
+                                quote! { &#ident }
+                            }
+                        }
+                    };
+
+                    spec.wrap(&krate, value)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let exprs = args.iter().map(|(_, expr)| expr);
+        let tuple = nested_tuple(parts);
+
+        Ok(quote! {
+            match (#(#exprs,)*) {
+                (#(#bindings,)*) => #krate::string::StringExtT::to_string_ext(#tuple),
+            }
+        })
+    }
+}
+
+/// Tuples with at most 32 elements implement `StringT`, nest them if needed.
+fn nested_tuple(parts: Vec<TokenStream2>) -> TokenStream2 {
+    const MAX_TUPLE_LEN: usize = 32;
+
+    if parts.len() <= MAX_TUPLE_LEN {
+        quote! { (#(#parts,)*) }
+    } else {
+        nested_tuple(
+            parts
+                .chunks(MAX_TUPLE_LEN)
+                .map(|chunk| nested_tuple(chunk.to_vec()))
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Placeholder { name: String, spec: Spec },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Spec {
+    /// `:08`
+    min_len: Option<usize>,
+    kind: SpecKind,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SpecKind {
+    Plain,
+    /// `:hex` or `:x`
+    Hex,
+    /// `:HEX` or `:X`
+    HexUpper,
+    /// `:b64`
+    Base64,
+    /// `:url`
+    UrlEncode,
+}
+
+impl Spec {
+    const fn is_number(&self) -> bool {
+        self.min_len.is_some() || matches!(self.kind, SpecKind::Hex | SpecKind::HexUpper)
+    }
+
+    fn wrap(&self, krate: &TokenStream2, value: TokenStream2) -> TokenStream2 {
+        let value = match self.kind {
+            SpecKind::Plain if self.min_len.is_none() => return value,
+            SpecKind::Plain => quote! { #krate::string::NumStr::new_default(#value) },
+            SpecKind::Hex => quote! { #krate::string::NumStr::new_default(#value).hexadecimal() },
+            SpecKind::HexUpper => quote! {
+                #krate::string::NumStr::new_default(#value)
+                    .hexadecimal()
+                    .set_uppercase::<true>()
+            },
+            SpecKind::Base64 => {
+                return quote! { #krate::string::b64_padding::STANDARD::encode(#value) }
+            }
+            SpecKind::UrlEncode => return quote! { #krate::urlencoding_str!(E: #value) },
+        };
+
+        match self.min_len {
+            Some(min_len) => {
+                let min_len = Literal::usize_unsuffixed(min_len);
+                quote! { #value.set_minimum_len::<#min_len>() }
+            }
+            None => value,
+        }
+    }
+}
+
+/// Parse the template, `{{` and `}}` are escaped braces.
+fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("unclosed `{`, use `{{` to escape".to_string()),
+                    }
+                }
+
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+
+                segments.push(parse_placeholder(&placeholder)?);
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("unmatched `}`, use `}}` to escape".to_string()),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+/// Parse `name` or `name:spec`.
+fn parse_placeholder(placeholder: &str) -> Result<Segment, String> {
+    let (name, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+
+    let is_valid_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    if !is_valid_name {
+        return Err(format!(
+            "invalid placeholder `{{{placeholder}}}`, expected `{{name}}` or `{{name:spec}}`"
+        ));
+    }
+
+    let (min_len, kind) = match spec.strip_prefix('0') {
+        Some(rest) => {
+            let (digits, kind) = rest.split_at(
+                rest.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len()),
+            );
+
+            let min_len = digits
+                .parse()
+                .map_err(|_| format!("invalid width in format spec `{spec}`"))?;
+
+            (Some(min_len), kind)
+        }
+        None => (None, spec),
+    };
+
+    let kind = match kind {
+        "" => SpecKind::Plain,
+        "hex" | "x" => SpecKind::Hex,
+        "HEX" | "X" => SpecKind::HexUpper,
+        "b64" => SpecKind::Base64,
+        "url" => SpecKind::UrlEncode,
+        _ => {
+            return Err(format!(
+                "unknown format spec `{spec}`, expected `hex`, `HEX`, `b64`, `url` or `0<width>` \
+                 (optionally followed by `hex` or `HEX`)"
+            ))
+        }
+    };
+
+    if min_len.is_some() && matches!(kind, SpecKind::Base64 | SpecKind::UrlEncode) {
+        return Err(format!(
+            "width in format spec `{spec}` is only supported for numbers"
+        ));
+    }
+
+    Ok(Segment::Placeholder {
+        name: name.to_string(),
+        spec: Spec { min_len, kind },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        assert_eq!(
+            parse_template("a={a} {{b}}={b:08hex}}}").unwrap(),
+            vec![
+                Segment::Literal("a=".to_string()),
+                Segment::Placeholder {
+                    name: "a".to_string(),
+                    spec: Spec {
+                        min_len: None,
+                        kind: SpecKind::Plain
+                    }
+                },
+                Segment::Literal(" {b}=".to_string()),
+                Segment::Placeholder {
+                    name: "b".to_string(),
+                    spec: Spec {
+                        min_len: Some(8),
+                        kind: SpecKind::Hex
+                    }
+                },
+                Segment::Literal("}".to_string()),
+            ]
+        );
+
+        assert!(parse_template("{a").is_err());
+        assert!(parse_template("a}").is_err());
+        assert!(parse_template("{}").is_err());
+        assert!(parse_template("{1}").is_err());
+        assert!(parse_template("{a:oct}").is_err());
+        assert!(parse_template("{a:0}").is_err());
+        assert!(parse_template("{a:08b64}").is_err());
+    }
+}
@@ -155,7 +155,7 @@ mod tests {
     wrapper!(pub MyStringDerived(String), derive(Debug, Clone, PartialEq, Eq, Hash));
     wrapper! {
         #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-        /// MyStringDerived
+        /// `MyStringDerived`
         pub MyStringDerived2(String)
     }
     wrapper!(pub MyStringPubInnerDerived(pub String), derive(Debug, Clone, PartialEq, Eq, Hash));
@@ -6,22 +6,35 @@ pub mod externs;
 pub mod general;
 #[cfg(feature = "feat-string-ext-hex")]
 pub mod hex;
+#[cfg(feature = "feat-string-ext-html")]
+pub mod html;
+#[cfg(feature = "feat-string-ext-json")]
+pub mod json;
 pub mod number;
 #[cfg(feature = "feat-string-ext-rand")]
 pub mod rand;
+pub mod stack;
 #[cfg(feature = "feat-string-ext-urlencoding")]
 pub mod urlencoding;
+pub mod writer;
 
 #[cfg(feature = "feat-string-ext-base64")]
 pub use base64::b64_padding;
 #[cfg(feature = "feat-string-ext-hex")]
 // Re-export the `HexStr` type for convenience.
 pub use hex::HexStr;
+#[cfg(feature = "feat-string-ext-json")]
+// Re-export the JSON types for convenience.
+pub use json::{JsonArray, JsonObject, JsonStr};
 // Re-export the `NumStr` type for convenience.
 pub use number::NumStr;
 #[cfg(feature = "feat-string-ext-rand")]
 // Re-export the `RandHexStr`, `RandStr` type for convenience.
 pub use rand::{RandHexStr, RandStr};
+// Re-export the `StackString` type for convenience.
+pub use stack::StackString;
+// Re-export the `DisplayWrapper` type for convenience.
+pub use writer::DisplayWrapper;
 
 wrapper! {
     #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -66,6 +79,11 @@ wrapper! {
 /// assert_eq!(
 ///   str_concat!(sep = ","; "abc", "1", "😀", "�"), "abc,1,😀,�"
 /// );
+///
+/// // on the stack, with fixed capacity (in bytes), truncated if overflow
+/// assert_eq!(
+///   str_concat!(stack = 8; "abc", "1", "😀", "�"), "abc1😀"
+/// );
 /// # }
 /// ```
 macro_rules! str_concat {
@@ -105,24 +123,67 @@ macro_rules! str_concat {
             ($($x,)*).to_string_ext_with_separator($sep)
         }
     };
+    (stack = $cap:expr; $($x:expr),*) => {
+        {
+            use $crate::string::PushAnyT;
+
+            let mut string_final = $crate::string::StackString::<$cap>::new();
+
+            $(
+                string_final.push_any($x);
+            )*
+
+            string_final
+        }
+    };
 }
 
-#[deprecated(since = "0.8.0", note = "Use `str_concat!` instead")]
-pub use crate::str_concat as str_concat_v2;
-use crate::wrapper;
+#[cfg(feature = "feat-string-template")]
+#[doc(hidden)]
+pub use macro_toolset_macros::str_template as __str_template;
 
-/// Remove the trailing separator from the string.
+#[cfg(feature = "feat-string-template")]
+#[macro_export]
+/// Concat [`String`] with a template, which is parsed at compile time into
+/// the same tuple pipeline as [`str_concat!`].
 ///
-/// Notice: will not check if the separator exists or not!
-macro_rules! remove_separator_tailing {
-    ($string:expr, $separator:expr) => {
-        let current_len = $string.len();
-        if let Some(target_len) = current_len.checked_sub($separator.len()) {
-            $string.truncate(target_len);
-        }
+/// Placeholders are like `{name}` or `{name:spec}`, where `name` is a named
+/// argument or (like [`format!`]) a variable in scope, captured by reference
+/// (by value for numbers with spec). Use `{{` and `}}` to escape braces.
+///
+/// Named arguments used multiple times are cloned.
+///
+/// Supported `spec`s:
+///
+/// - `hex` / `HEX` (or `x` / `X`): [`NumStr::hexadecimal`], lowercase /
+///   uppercase.
+/// - `0<width>`, e.g. `08`: [`NumStr::set_minimum_len`], can be followed by
+///   `hex` / `HEX`, e.g. `08hex`.
+/// - `b64`: [`Base64Str`](crate::string::base64::Base64Str), standard with
+///   padding, requires `feat-string-ext-base64`.
+/// - `url`: [`Encode`](crate::string::urlencoding::Encode), requires
+///   `feat-string-ext-urlencoding`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use macro_toolset::str_template;
+/// let name = "world";
+/// assert_eq!(
+///     str_template!("user={name} id={id:hex} seq={seq:08} {{ok}}", id = 255u32, seq = 42u8),
+///     "user=world id=ff seq=00000042 {ok}"
+/// );
+/// ```
+macro_rules! str_template {
+    ($($tt:tt)*) => {
+        $crate::string::__str_template!($crate; $($tt)*)
     };
 }
 
+#[deprecated(since = "0.8.0", note = "Use `str_concat!` instead")]
+pub use crate::str_concat as str_concat_v2;
+use crate::wrapper;
+
 /// Trait helper for push any string-like type to the string.
 pub trait PushAnyT {
     /// Push any string-like type to the string.
@@ -130,7 +191,8 @@ pub trait PushAnyT {
     where
         V: StringT;
 
-    /// Push any string-like type to the string with a separator.
+    /// Push any string-like type to the string, with the separator inserted
+    /// between non-empty items. See [`StringT::encode_to_buf_with_separator`].
     fn push_any_with_separator<V>(&mut self, value: V, sep: &str)
     where
         V: StringT;
@@ -144,7 +206,7 @@ impl PushAnyT for String {
     {
         // safe because of the `StringT` trait
         #[allow(unsafe_code)]
-        value.encode_to_buf(unsafe { self.as_mut_vec() });
+        unsafe { self.as_mut_vec() }.push_any(value);
     }
 
     #[inline]
@@ -154,21 +216,17 @@ impl PushAnyT for String {
     {
         // safe because of the `StringT` trait
         #[allow(unsafe_code)]
-        let inner = unsafe { self.as_mut_vec() };
-
-        value.encode_to_buf_with_separator(inner, sep);
-
-        // If is `None`?
-        remove_separator_tailing!(inner, sep);
+        unsafe { self.as_mut_vec() }.push_any_with_separator(value, sep);
     }
 }
 
-impl PushAnyT for Vec<u8> {
+impl<B: BufT> PushAnyT for B {
     #[inline]
     fn push_any<V>(&mut self, value: V)
     where
         V: StringT,
     {
+        self.reserve(len_hint_reserve(value.len_hint()));
         value.encode_to_buf(self);
     }
 
@@ -177,49 +235,366 @@ impl PushAnyT for Vec<u8> {
     where
         V: StringT,
     {
-        value.encode_to_buf_with_separator(self, sep);
-        remove_separator_tailing!(self, sep);
+        self.reserve(len_hint_reserve(value.len_hint()));
+        value.encode_to_buf_with_separator(self, &mut Separator::new(sep));
     }
 }
 
-impl PushAnyT for bytes::BytesMut {
+/// Trait for the underlying buffer that string-like types are encoded to.
+///
+/// This crate has implemented it for `Vec<u8>` and `bytes::BytesMut`, and you
+/// may implement it for your own buffer types like `smallvec::SmallVec`, stack
+/// arrays, or arenas, then [`PushAnyT`] will be available for them.
+///
+/// Notice: [`StringT`] implementations must only write valid UTF-8 bytes, and
+/// may modify the bytes written by themselves in place.
+pub trait BufT {
+    /// Returns the number of bytes in the buffer.
+    fn len(&self) -> usize;
+
     #[inline]
-    fn push_any<V>(&mut self, value: V)
-    where
-        V: StringT,
-    {
-        value.encode_to_bytes_buf(self);
+    /// Returns `true` if the buffer contains no bytes.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
+    /// Reserves capacity for at least `additional` more bytes.
+    fn reserve(&mut self, additional: usize);
+
+    /// Appends a byte to the back of the buffer.
+    fn push(&mut self, byte: u8);
+
+    /// Appends all bytes in the slice to the back of the buffer.
+    fn extend_from_slice(&mut self, bytes: &[u8]);
+
+    /// Resizes the buffer so that `len` is equal to `new_len`, the new part
+    /// will be filled with `value`.
+    fn resize(&mut self, new_len: usize, value: u8);
+
+    /// Shortens the buffer, keeping the first `len` bytes and dropping the
+    /// rest.
+    fn truncate(&mut self, len: usize);
+
+    /// Returns the bytes written as a mutable slice.
+    ///
+    /// Buffers with a fixed capacity, like the one [`StackString`] writes to,
+    /// discard the bytes beyond it without allocating, so the slice may be
+    /// shorter than [`len`](BufT::len). Access it with `get` or `get_mut`
+    /// instead of indexing, and skip modifying in place if it is too short.
+    fn as_mut_slice(&mut self) -> &mut [u8];
+
     #[inline]
-    fn push_any_with_separator<V>(&mut self, value: V, sep: &str)
+    /// Hint that the bytes written so far are complete items and will not be
+    /// modified in place anymore, so that streaming buffers may flush them.
+    ///
+    /// Called between items of tuples and iterators. The default
+    /// implementation does nothing.
+    ///
+    /// [`StringT`] implementations modifying the bytes written by the inner
+    /// value in place must write it through [`Uncommitted`], or the bytes may
+    /// have been flushed already.
+    fn commit(&mut self) {}
+}
+
+#[derive(Debug)]
+/// [`BufT`] adapter ignoring [`BufT::commit`], for [`StringT`]
+/// implementations which modify the bytes written by the inner value in place
+/// (like escaping or padding).
+///
+/// # Example
+///
+/// ```rust
+/// # use macro_toolset::string::{BufT, StringT, Uncommitted};
+/// struct Upper<T>(T);
+///
+/// impl<T: StringT> StringT for Upper<T> {
+///     fn encode_to_buf<B: BufT>(self, string: &mut B) {
+///         let start = string.len();
+///
+///         self.0.encode_to_buf(&mut Uncommitted(&mut *string));
+///
+///         if let Some(written) = string.as_mut_slice().get_mut(start..) {
+///             written.make_ascii_uppercase();
+///         }
+///     }
+/// }
+/// ```
+pub struct Uncommitted<'b, B: ?Sized>(pub &'b mut B);
+
+impl<B: BufT + ?Sized> BufT for Uncommitted<'_, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
+    #[inline]
+    fn push(&mut self, byte: u8) {
+        self.0.push(byte);
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    fn resize(&mut self, new_len: usize, value: u8) {
+        self.0.resize(new_len, value);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.0.as_mut_slice()
+    }
+}
+
+impl BufT for Vec<u8> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    #[inline]
+    fn push(&mut self, byte: u8) {
+        self.push(byte);
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    fn resize(&mut self, new_len: usize, value: u8) {
+        self.resize(new_len, value);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl BufT for bytes::BytesMut {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    #[inline]
+    fn push(&mut self, byte: u8) {
+        bytes::BufMut::put_u8(self, byte);
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    fn resize(&mut self, new_len: usize, value: u8) {
+        self.resize(new_len, value);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The separator, with the state of whether any non-empty item has been
+/// written, see [`StringT::encode_to_buf_with_separator`].
+pub struct Separator<'s> {
+    separator: &'s str,
+    wrote_any: bool,
+}
+
+impl<'s> Separator<'s> {
+    #[inline]
+    /// Create a new separator state, nothing written yet.
+    pub const fn new(separator: &'s str) -> Self {
+        Self {
+            separator,
+            wrote_any: false,
+        }
+    }
+
+    #[inline]
+    /// Returns the separator.
+    pub const fn as_str(&self) -> &'s str {
+        self.separator
+    }
+
+    #[inline]
+    /// Returns `true` if any non-empty item has been written.
+    pub const fn wrote_any(&self) -> bool {
+        self.wrote_any
+    }
+
+    #[inline]
+    /// Encode the value as a single item, the separator is written before it
+    /// if it's not empty and not the first non-empty item.
+    pub fn encode_item<T, B>(&mut self, item: T, string: &mut B)
     where
-        V: StringT,
+        T: StringT,
+        B: BufT,
     {
-        value.encode_to_bytes_buf_with_separator(self, sep);
-        remove_separator_tailing!(self, sep);
+        let original_len = string.len();
+
+        if self.wrote_any {
+            string.extend_from_slice(self.separator.as_bytes());
+        }
+
+        let item_start = string.len();
+
+        item.encode_to_buf(&mut Uncommitted(&mut *string));
+
+        if string.len() == item_start {
+            // Nothing written, remove the separator just written (if any).
+            string.truncate(original_len);
+        } else {
+            self.wrote_any = true;
+        }
     }
 }
 
 /// Trait for string-like types.
 pub trait StringT {
-    /// Push the value to the string (the underlying `Vec<u8>`).
-    fn encode_to_buf(self, string: &mut Vec<u8>);
+    /// Push the value to the string (the underlying buffer).
+    fn encode_to_buf<B: BufT>(self, string: &mut B)
+    where
+        Self: Sized;
 
-    /// Push the value to the string (the underlying `Vec<u8>`) with a
-    /// separator.
+    #[inline]
+    /// Push the value to the string (the underlying buffer), with the
+    /// separator inserted between non-empty items.
     ///
-    /// The will be a tailing separator.
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str);
+    /// Containers like tuples and iterators should pass the separator state
+    /// to each item, while the default implementation treats the value as a
+    /// single item.
+    fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>)
+    where
+        Self: Sized,
+    {
+        separator.encode_item(self, string);
+    }
 
+    #[inline]
+    #[deprecated(since = "0.8.3", note = "Use `encode_to_buf` instead")]
     /// Push the value to the string (the underlying `bytes::BytesMut`).
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut);
+    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut)
+    where
+        Self: Sized,
+    {
+        self.encode_to_buf(string);
+    }
 
+    #[inline]
+    #[deprecated(since = "0.8.3", note = "Use `encode_to_buf_with_separator` instead")]
     /// Push the value to the string (the underlying `bytes::BytesMut`) with a
     /// separator.
+    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str)
+    where
+        Self: Sized,
+    {
+        self.encode_to_buf_with_separator(string, &mut Separator::new(separator));
+    }
+
+    #[inline]
+    /// Returns the bounds on the length (in bytes) of the encoded value, just
+    /// like [`Iterator::size_hint`].
+    ///
+    /// The first element is the lower bound, and the second one is the upper
+    /// bound (`None` means unknown). Separators are not counted.
     ///
-    /// The will be a tailing separator.
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str);
+    /// This is used for reserving capacity in advance, a wrong hint will not
+    /// lead to incorrect result but extra (re)allocation only.
+    ///
+    /// The default implementation returns `(0, None)`.
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    #[inline]
+    /// Write the value to the [`std::fmt::Write`], like a
+    /// [`std::fmt::Formatter`], chunk by chunk.
+    fn write_to_fmt<W>(self, writer: &mut W) -> std::fmt::Result
+    where
+        Self: Sized,
+        W: std::fmt::Write + ?Sized,
+    {
+        writer::write_to_fmt(self, writer)
+    }
+
+    #[inline]
+    /// Write the value to the [`std::io::Write`], like a `File` or a
+    /// `TcpStream`, chunk by chunk.
+    ///
+    /// Notice: the writer is not flushed.
+    fn write_to_io<W>(self, writer: &mut W) -> std::io::Result<()>
+    where
+        Self: Sized,
+        W: std::io::Write + ?Sized,
+    {
+        writer::write_to_io(self, writer)
+    }
+}
+
+#[inline]
+/// Add up two length hints, see [`StringT::len_hint`].
+pub(crate) const fn len_hint_add(
+    lhs: (usize, Option<usize>),
+    rhs: (usize, Option<usize>),
+) -> (usize, Option<usize>) {
+    (
+        lhs.0.saturating_add(rhs.0),
+        match (lhs.1, rhs.1) {
+            (Some(lhs), Some(rhs)) => lhs.checked_add(rhs),
+            _ => None,
+        },
+    )
+}
+
+#[inline]
+/// The capacity to reserve for the given length hint.
+///
+/// Prefer the upper bound so that we can allocate exactly once.
+pub(crate) const fn len_hint_reserve(hint: (usize, Option<usize>)) -> usize {
+    match hint {
+        (_, Some(upper)) => upper,
+        (lower, None) => lower,
+    }
 }
 
 #[allow(clippy::len_without_is_empty)]
@@ -242,10 +617,33 @@ pub trait StringExtT: StringT + Sized {
         }
     }
 
+    #[inline]
+    /// Join items of the value with its own separator, see
+    /// [`Joined`](general::joined::Joined).
+    fn joined(self, separator: &str) -> general::joined::Joined<'_, Self> {
+        general::joined::Joined::new(self, separator)
+    }
+
+    #[inline]
+    /// Pad the value to the given width, see
+    /// [`Padded`](general::padded::Padded).
+    fn padded(self, width: usize) -> general::padded::Padded<Self> {
+        general::padded::Padded::new(self, width)
+    }
+
+    #[inline]
+    /// Limit the output to the given length (in bytes by default), see
+    /// [`Truncated`](general::truncated::Truncated).
+    fn truncated(self, max_len: usize) -> general::truncated::Truncated<'static, Self> {
+        general::truncated::Truncated::new(self, max_len)
+    }
+
     #[inline]
     /// Encode the value to the string.
+    ///
+    /// The capacity is calculated from [`StringT::len_hint`] in advance.
     fn to_string_ext(self) -> String {
-        let mut string_buf = String::with_capacity(64);
+        let mut string_buf = String::with_capacity(to_string_capacity(self.len_hint()));
 
         string_buf.push_any(self);
 
@@ -255,13 +653,20 @@ pub trait StringExtT: StringT + Sized {
     #[inline]
     /// Encode the value(s) to the string with separator.
     fn to_string_ext_with_separator(self, separator: &str) -> String {
-        let mut string_buf = String::with_capacity(64);
+        let mut string_buf = String::with_capacity(to_string_capacity(self.len_hint()));
 
         string_buf.push_any_with_separator(self, separator);
 
         string_buf
     }
 
+    #[inline]
+    /// Wrap the value with [`DisplayWrapper`], which implements
+    /// [`Display`](std::fmt::Display).
+    fn display(self) -> DisplayWrapper<Self> {
+        DisplayWrapper::new(self)
+    }
+
     #[inline]
     #[cfg(feature = "feat-string-ext-http")]
     /// Encode the value to the string as a HTTP header value.
@@ -275,6 +680,18 @@ pub trait StringExtT: StringT + Sized {
     }
 }
 
+#[inline]
+/// The initial capacity of the string created by
+/// [`StringExtT::to_string_ext`], at least 64 bytes if the upper bound is
+/// unknown.
+const fn to_string_capacity(hint: (usize, Option<usize>)) -> usize {
+    match hint {
+        (_, Some(upper)) => upper,
+        (lower, None) if lower > 64 => lower,
+        (_, None) => 64,
+    }
+}
+
 // =============================================================================
 
 #[doc(hidden)]
@@ -284,23 +701,18 @@ macro_rules! impl_for_shared_ref {
         $(
             impl StringT for &$ty {
                 #[inline]
-                fn encode_to_buf(self, string: &mut Vec<u8>) {
+                fn encode_to_buf<B: BufT>(self, string: &mut B) {
                     (*self).encode_to_buf(string);
                 }
 
                 #[inline]
-                fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+                fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                     (*self).encode_to_buf_with_separator(string, separator);
                 }
 
                 #[inline]
-                fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                    (*self).encode_to_bytes_buf(string);
-                }
-
-                #[inline]
-                fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                    (*self).encode_to_bytes_buf_with_separator(string, separator);
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    (**self).len_hint()
                 }
             }
 
@@ -308,23 +720,18 @@ macro_rules! impl_for_shared_ref {
 
             impl StringT for &mut $ty {
                 #[inline]
-                fn encode_to_buf(self, string: &mut Vec<u8>) {
+                fn encode_to_buf<B: BufT>(self, string: &mut B) {
                     (*self).encode_to_buf(string);
                 }
 
                 #[inline]
-                fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+                fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                     (*self).encode_to_buf_with_separator(string, separator);
                 }
 
                 #[inline]
-                fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                    (*self).encode_to_bytes_buf(string);
-                }
-
-                #[inline]
-                fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                    (*self).encode_to_bytes_buf_with_separator(string, separator);
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    (**self).len_hint()
                 }
             }
 
@@ -332,23 +739,18 @@ macro_rules! impl_for_shared_ref {
 
             impl StringT for &&$ty {
                 #[inline]
-                fn encode_to_buf(self, string: &mut Vec<u8>) {
+                fn encode_to_buf<B: BufT>(self, string: &mut B) {
                     (**self).encode_to_buf(string);
                 }
 
                 #[inline]
-                fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+                fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                     (**self).encode_to_buf_with_separator(string, separator);
                 }
 
                 #[inline]
-                fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                    (**self).encode_to_bytes_buf(string);
-                }
-
-                #[inline]
-                fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                    (**self).encode_to_bytes_buf_with_separator(string, separator);
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    (***self).len_hint()
                 }
             }
 
@@ -356,23 +758,18 @@ macro_rules! impl_for_shared_ref {
 
             impl StringT for &mut &$ty {
                 #[inline]
-                fn encode_to_buf(self, string: &mut Vec<u8>) {
+                fn encode_to_buf<B: BufT>(self, string: &mut B) {
                     (**self).encode_to_buf(string);
                 }
 
                 #[inline]
-                fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+                fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                     (**self).encode_to_buf_with_separator(string, separator);
                 }
 
                 #[inline]
-                fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                    (**self).encode_to_bytes_buf(string);
-                }
-
-                #[inline]
-                fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                    (**self).encode_to_bytes_buf_with_separator(string, separator);
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    (***self).len_hint()
                 }
             }
 
@@ -380,23 +777,18 @@ macro_rules! impl_for_shared_ref {
 
             impl StringT for &&mut $ty {
                 #[inline]
-                fn encode_to_buf(self, string: &mut Vec<u8>) {
+                fn encode_to_buf<B: BufT>(self, string: &mut B) {
                     (**self).encode_to_buf(string);
                 }
 
                 #[inline]
-                fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+                fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                     (**self).encode_to_buf_with_separator(string, separator);
                 }
 
                 #[inline]
-                fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                    (**self).encode_to_bytes_buf(string);
-                }
-
-                #[inline]
-                fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                    (**self).encode_to_bytes_buf_with_separator(string, separator);
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    (***self).len_hint()
                 }
             }
 
@@ -404,23 +796,18 @@ macro_rules! impl_for_shared_ref {
 
             impl StringT for &&&$ty {
                 #[inline]
-                fn encode_to_buf(self, string: &mut Vec<u8>) {
+                fn encode_to_buf<B: BufT>(self, string: &mut B) {
                     (***self).encode_to_buf(string);
                 }
 
                 #[inline]
-                fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+                fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                     (***self).encode_to_buf_with_separator(string, separator);
                 }
 
                 #[inline]
-                fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                    (***self).encode_to_bytes_buf(string);
-                }
-
-                #[inline]
-                fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                    (***self).encode_to_bytes_buf_with_separator(string, separator);
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    (****self).len_hint()
                 }
             }
 
@@ -434,23 +821,18 @@ macro_rules! impl_for_shared_ref {
                 for <'a> &'a $ge: StringT,
             {
                 #[inline]
-                fn encode_to_buf(self, string: &mut Vec<u8>) {
+                fn encode_to_buf<B: BufT>(self, string: &mut B) {
                     (&*self).encode_to_buf(string);
                 }
 
                 #[inline]
-                fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+                fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                     (&*self).encode_to_buf_with_separator(string, separator);
                 }
 
                 #[inline]
-                fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                    (&*self).encode_to_bytes_buf(string);
-                }
-
-                #[inline]
-                fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                    (&*self).encode_to_bytes_buf_with_separator(string, separator);
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    <&$ge as StringT>::len_hint(&&**self)
                 }
             }
 
@@ -469,23 +851,18 @@ macro_rules! impl_for_wrapper {
     (STRING_T: $($tt:tt)*) => {
         $($tt)* {
             #[inline]
-            fn encode_to_buf(self, string: &mut Vec<u8>) {
+            fn encode_to_buf<B: BufT>(self, string: &mut B) {
                 (&*self.inner).encode_to_buf(string);
             }
 
             #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+            fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                 (&*self.inner).encode_to_buf_with_separator(string, separator);
             }
 
             #[inline]
-            fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                (&*self.inner).encode_to_bytes_buf(string);
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                (&*self.inner).encode_to_bytes_buf_with_separator(string, separator);
+            fn len_hint(&self) -> (usize, Option<usize>) {
+                (&*self.inner).len_hint()
             }
         }
     };
@@ -552,6 +929,35 @@ mod tests {
         assert_eq!(exp6, "post_ids[]=1&post_ids[]=2&post_ids[]=3")
     }
 
+    #[test]
+    fn test_len_hint() {
+        assert_eq!("hello".len_hint(), (5, Some(5)));
+        assert_eq!('😀'.len_hint(), (4, Some(4)));
+        assert_eq!(None::<&str>.len_hint(), (0, Some(0)));
+        assert_eq!(Ok::<_, ()>("hello").len_hint(), (5, Some(5)));
+        assert_eq!(vec!["a", "bc"].len_hint(), (3, Some(3)));
+        assert_eq!((&["a", "bc"][..]).len_hint(), (3, Some(3)));
+        assert_eq!(["a", "bc"].into_iter().map(str::len).len_hint(), (0, None));
+        assert_eq!(
+            (
+                "hello",
+                ' ',
+                "world".with_prefix("the ").with_suffix('!'),
+                123_u8
+            )
+                .len_hint(),
+            (19, Some(19))
+        );
+
+        let string = str_concat!("hello", ' ', "world", 12345_u32, Some("!"), vec![1_u8, 2]);
+        assert_eq!(string, "hello world12345!12");
+        assert_eq!(string.capacity(), string.len());
+
+        let mut string = String::new();
+        string.push_any(("hello", ' ', "world"));
+        assert_eq!(string.capacity(), string.len());
+    }
+
     #[test]
     fn test_separator() {
         let mut string = String::new();
@@ -587,4 +993,87 @@ mod tests {
             "abca,b,ca,b,c,b,ca,b,c,b,c,prefix-d,e-suffix,2prefix-f-suffix2,1"
         );
     }
+
+    #[test]
+    #[cfg(feature = "feat-string-template")]
+    fn test_str_template() {
+        let user = "alice".to_string();
+        let id = 0xabu16;
+
+        assert_eq!(str_template!(""), "");
+        assert_eq!(str_template!("{{}}"), "{}");
+        assert_eq!(str_template!("{user}:{id}"), "alice:171");
+        assert_eq!(str_template!("{id:hex}-{id:HEX}-{id:06X}"), "ab-AB-0000AB");
+        assert_eq!(
+            str_template!("{a}{b:04}{a}", a = user.clone(), b = 7u8),
+            "alice0007alice"
+        );
+        // Not moved.
+        assert_eq!(user, "alice");
+
+        // More than 32 segments.
+        assert_eq!(
+            str_template!(
+                "{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},{a},",
+                a = 1u8
+            ),
+            "1,".repeat(20)
+        );
+
+        #[cfg(feature = "feat-string-ext-base64")]
+        assert_eq!(str_template!("{data:b64}", data = b"hello"), "aGVsbG8=");
+
+        #[cfg(feature = "feat-string-ext-urlencoding")]
+        assert_eq!(str_template!("q={user:url}", user = "a b"), "q=a%20b");
+    }
+
+    #[test]
+    fn test_separator_empty_items() {
+        // Trailing `None` after content must not truncate real data.
+        assert_eq!(str_concat!(sep = ","; "a", "b", None::<&str>), "a,b");
+        assert_eq!(
+            str_concat!(sep = ","; None::<&str>, "a", None::<&str>, "b"),
+            "a,b"
+        );
+        assert_eq!(str_concat!(sep = ","; Some("a"), Some("b")), "a,b");
+        assert_eq!(str_concat!(sep = ", "; None::<&str>, None::<&str>), "");
+
+        assert_eq!(
+            str_concat!(sep = ","; Ok::<_, ()>("a"), Err::<&str, _>("b"), "c"),
+            "a,c"
+        );
+
+        // Empty containers and empty strings.
+        assert_eq!(str_concat!(sep = ","; "a", Vec::<&str>::new()), "a");
+        assert_eq!(str_concat!(sep = ","; Vec::<&str>::new(), "a", ""), "a");
+        assert_eq!(str_concat!(sep = ","; "a", (), vec!["", "b"], "c"), "a,b,c");
+
+        // Nested tuples are flattened.
+        assert_eq!(
+            str_concat!(sep = "-"; ("a", (None::<&str>, ("b", "")), ()), ("c",)),
+            "a-b-c"
+        );
+
+        // Appending to existing content does not insert a leading separator.
+        let mut string = "x".to_string();
+        string.push_any_with_separator(("a", None::<&str>), ",");
+        assert_eq!(string, "xa");
+        string.push_any_with_separator(None::<&str>, ",");
+        assert_eq!(string, "xa");
+
+        // Prefixed / suffixed values are single items.
+        assert_eq!(
+            str_concat!(sep = ","; "a".with_prefix("<"), None::<&str>, "b".with_suffix(">")),
+            "<a,b>"
+        );
+        assert_eq!(
+            str_concat!(sep = ","; ("a", "b").with_prefix("-"), ("c", "d").with_suffix("!")),
+            "-a,-b,c!,d!"
+        );
+        // The prefix of `None` is not written either.
+        assert_eq!(
+            str_concat!(sep = ","; None::<&str>.with_prefix("p"), "", "q"),
+            "q"
+        );
+    }
 }
@@ -2,7 +2,7 @@
 
 use std::{marker::PhantomData, ops};
 
-use super::{NumStr, StringExtT, StringT};
+use super::{BufT, NumStr, StringExtT, StringT};
 
 pub mod b64_padding {
     //! Base64 padding
@@ -11,8 +11,8 @@ pub mod b64_padding {
     //! to represent the padding, convenient to use and performance improvement.
 
     use super::{
-        Base64EncoderT, Base64Str, Decode, DecodeToAny, DecodeToHex, Encode, NumStr, PhantomData,
-        StringExtT, StringT,
+        decode_to_buf, Base64EncoderT, Base64Str, BufT, Decode, DecodeToAny, DecodeToHex, Encode,
+        NumStr, PhantomData, StringExtT, StringT,
     };
 
     macro_rules! enum_padding {
@@ -80,65 +80,37 @@ pub mod b64_padding {
 
                 impl<T: AsRef<[u8]>> StringT for Base64Str<T, $name, Encode> {
                     #[inline]
-                    fn encode_to_buf(self, string: &mut Vec<u8>) {
+                    fn encode_to_buf<B: BufT>(self, string: &mut B) {
                         let inner = self.inner.as_ref();
 
                         let current_len = string.len();
                         let base64_len = inner.len() * 4 / 3 + 4;
                         let target_len = current_len + base64_len;
 
-                        string.reserve_exact(base64_len);
-                        #[allow(unsafe_code)]
-                        // Safety: have reserved and allocate enough space
-                        unsafe {
-                            string.set_len(target_len);
-                        }
+                        string.resize(target_len, 0);
 
-                        let bytes_written = base64::Engine::encode_slice(
-                            &base64::engine::general_purpose::$name,
-                            self.inner,
-                            &mut string[current_len..target_len],
-                        )
-                        .unwrap_or(0);
+                        let bytes_written = string
+                            .as_mut_slice()
+                            .get_mut(current_len..target_len)
+                            .and_then(|buf| {
+                                base64::Engine::encode_slice(&base64::engine::general_purpose::$name, inner, buf).ok()
+                            })
+                            .unwrap_or(0);
 
                         string.truncate(current_len + bytes_written);
                     }
 
                     #[inline]
-                    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-                        self.encode_to_buf(string);
-                        string.extend(separator.as_bytes());
-                    }
-
-                    #[inline]
-                    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                        let inner = self.inner.as_ref();
-
-                        let current_len = string.len();
-                        let base64_len = inner.len() * 4 / 3 + 4;
-                        let target_len = current_len + base64_len;
-
-                        string.reserve(base64_len);
-                        #[allow(unsafe_code)]
-                        // Safety: have reserved and allocate enough space
-                        unsafe {
-                            string.set_len(target_len);
+                    fn len_hint(&self) -> (usize, Option<usize>) {
+                        use base64::engine::{Config, Engine};
+
+                        match base64::encoded_len(
+                            self.inner.as_ref().len(),
+                            base64::engine::general_purpose::$name.config().encode_padding(),
+                        ) {
+                            Some(len) => (len, Some(len)),
+                            None => (0, None),
                         }
-
-                        let bytes_written = base64::Engine::encode_slice(
-                            &base64::engine::general_purpose::$name,
-                            self.inner,
-                            &mut string[current_len..target_len],
-                        )
-                        .unwrap_or(0);
-
-                        string.truncate(current_len + bytes_written);
-                    }
-
-                    #[inline]
-                    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                        self.encode_to_bytes_buf(string);
-                        string.extend(separator.as_bytes());
                     }
                 }
 
@@ -146,41 +118,23 @@ pub mod b64_padding {
 
                 impl<T: AsRef<[u8]>> StringT for Base64Str<T, $name, Decode> {
                     #[inline]
-                    fn encode_to_buf(self, string: &mut Vec<u8>) {
-                        use base64::Engine;
-
-                        let data = base64::engine::general_purpose::$name
-                            .decode(self.inner.as_ref())
-                            .unwrap_or_default();
-
-                        if std::str::from_utf8(&data).is_ok() {
-                            string.extend(data)
-                        }
-                    }
-
-                    #[inline]
-                    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-                        self.encode_to_buf(string);
-                        string.extend(separator.as_bytes());
-                    }
-
-                    #[inline]
-                    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                        use base64::Engine;
+                    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+                        let current_len = string.len();
 
-                        let data = base64::engine::general_purpose::$name
-                            .decode(self.inner.as_ref())
-                            .unwrap_or_default();
+                        decode_to_buf(&base64::engine::general_purpose::$name, self.inner.as_ref(), string);
 
-                        if std::str::from_utf8(&data).is_ok() {
-                            string.extend(data)
+                        if string
+                            .as_mut_slice()
+                            .get(current_len..)
+                            .map_or(true, |written| std::str::from_utf8(written).is_err())
+                        {
+                            string.truncate(current_len);
                         }
                     }
 
                     #[inline]
-                    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                        self.encode_to_bytes_buf(string);
-                        string.extend(separator.as_bytes());
+                    fn len_hint(&self) -> (usize, Option<usize>) {
+                        (0, Some(base64::decoded_len_estimate(self.inner.as_ref().len())))
                     }
                 }
 
@@ -188,32 +142,13 @@ pub mod b64_padding {
 
                 impl<T: AsRef<[u8]>> StringT for Base64Str<T, $name, DecodeToAny> {
                     #[inline]
-                    fn encode_to_buf(self, string: &mut Vec<u8>) {
-                        use base64::Engine;
-
-                        let _ = base64::engine::general_purpose::$name
-                            .decode_vec(self.inner.as_ref(), string);
-                    }
-
-                    #[inline]
-                    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-                        self.encode_to_buf(string);
-                        string.extend(separator.as_bytes());
-                    }
-
-                    #[inline]
-                    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                        use base64::Engine;
-
-                        if let Ok(data) = base64::engine::general_purpose::$name.decode(self.inner.as_ref()) {
-                            string.extend(data);
-                        }
+                    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+                        decode_to_buf(&base64::engine::general_purpose::$name, self.inner.as_ref(), string);
                     }
 
                     #[inline]
-                    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                        self.encode_to_bytes_buf(string);
-                        string.extend(separator.as_bytes());
+                    fn len_hint(&self) -> (usize, Option<usize>) {
+                        (0, Some(base64::decoded_len_estimate(self.inner.as_ref().len())))
                     }
                 }
 
@@ -221,7 +156,7 @@ pub mod b64_padding {
 
                 impl<T: AsRef<[u8]>> StringT for Base64Str<T, $name, DecodeToHex> {
                     #[inline]
-                    fn encode_to_buf(self, string: &mut Vec<u8>) {
+                    fn encode_to_buf<B: BufT>(self, string: &mut B) {
                         use base64::Engine;
 
                         base64::engine::general_purpose::$name
@@ -233,27 +168,8 @@ pub mod b64_padding {
                     }
 
                     #[inline]
-                    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-                        self.encode_to_buf(string);
-                        string.extend(separator.as_bytes());
-                    }
-
-                    #[inline]
-                    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                        use base64::Engine;
-
-                        base64::engine::general_purpose::$name
-                            .decode(self.inner.as_ref())
-                            .unwrap_or_default()
-                            .into_iter()
-                            .map(NumStr::hex_byte_default)
-                            .encode_to_bytes_buf(string);
-                    }
-
-                    #[inline]
-                    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                        self.encode_to_bytes_buf(string);
-                        string.extend(separator.as_bytes());
+                    fn len_hint(&self) -> (usize, Option<usize>) {
+                        (0, Some(base64::decoded_len_estimate(self.inner.as_ref().len()) * 2))
                     }
                 }
 
@@ -265,6 +181,23 @@ pub mod b64_padding {
     enum_padding!(STANDARD STANDARD_NO_PAD URL_SAFE URL_SAFE_NO_PAD);
 }
 
+#[inline]
+/// Decode the Base64 string into the buffer, will write nothing if the input is
+/// not a valid Base64 string.
+fn decode_to_buf<E: base64::Engine, B: BufT>(engine: &E, input: &[u8], string: &mut B) {
+    let current_len = string.len();
+
+    string.resize(current_len + base64::decoded_len_estimate(input.len()), 0);
+
+    let bytes_written = string
+        .as_mut_slice()
+        .get_mut(current_len..)
+        .and_then(|buf| engine.decode_slice(input, buf).ok())
+        .unwrap_or(0);
+
+    string.truncate(current_len + bytes_written);
+}
+
 /// Marker trait
 pub trait Base64EncoderT: StringExtT {}
 
@@ -357,4 +290,22 @@ mod test {
             "11451419198100"
         );
     }
+
+    #[test]
+    fn test_len_hint() {
+        for data in ["", "h", "he", "hel", "hello world"] {
+            let encoded = b64_padding::STANDARD::encode(data);
+            let len = encoded.len_hint();
+            let encoded = encoded.to_string_ext();
+            assert_eq!(len, (encoded.len(), Some(encoded.len())));
+
+            let encoded = b64_padding::URL_SAFE_NO_PAD::encode(data);
+            let len = encoded.len_hint();
+            let encoded = encoded.to_string_ext();
+            assert_eq!(len, (encoded.len(), Some(encoded.len())));
+
+            let decoded = b64_padding::URL_SAFE_NO_PAD::decode(&encoded);
+            assert!(decoded.len_hint().1.unwrap() >= data.len());
+        }
+    }
 }
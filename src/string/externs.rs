@@ -1,24 +1,16 @@
 //! Implementations of extern crate types
 
 macro_rules! impl_for_extern_type {
-    ($(#[$outer:meta])* $type:ty: $self:ident, $arg:ident => $block:block) => {
+    ($(#[$outer:meta])* $type:ty: $self:ident, $arg:ident => $block:block $(len_hint => $len_hint:block)?) => {
         $(#[$outer])*
         impl super::StringT for $type {
             #[inline]
-            fn encode_to_buf($self: Self, $arg: &mut Vec<u8>) $block
+            fn encode_to_buf<B: super::BufT>($self: Self, $arg: &mut B) $block
 
-            #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, _separator: &str) {
-                self.encode_to_buf(string);
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf($self: Self, $arg: &mut bytes::BytesMut) $block
-
-            #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, _separator: &str) {
-                self.encode_to_bytes_buf(string);
-            }
+            $(
+                #[inline]
+                fn len_hint(&$self) -> (usize, Option<usize>) $len_hint
+            )?
         }
 
         $(#[$outer])*
@@ -27,29 +19,29 @@ macro_rules! impl_for_extern_type {
 }
 
 #[cfg(feature = "feat-string-ext-ammonia")]
-impl super::StringT for ammonia::Document {
-    #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
-        self.write_to(string)
-            .expect("Writing to a string should not fail (except on OOM)");
-    }
+/// Adapter for writing to [`BufT`](super::BufT) through [`std::io::Write`].
+struct IoWriter<'b, B>(&'b mut B);
 
+#[cfg(feature = "feat-string-ext-ammonia")]
+impl<B: super::BufT> std::io::Write for IoWriter<'_, B> {
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, _separator: &str) {
-        self.encode_to_buf(string);
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
     }
 
     #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        use bytes::BufMut;
-
-        self.write_to(string.writer())
-            .expect("Writing to a string should not fail (except on OOM)");
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
     }
+}
 
+#[cfg(feature = "feat-string-ext-ammonia")]
+impl super::StringT for ammonia::Document {
     #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, _separator: &str) {
-        self.encode_to_bytes_buf(string);
+    fn encode_to_buf<B: super::BufT>(self, string: &mut B) {
+        self.write_to(IoWriter(string))
+            .expect("Writing to a string should not fail (except on OOM)");
     }
 }
 
@@ -58,46 +50,39 @@ impl<'a, I: Iterator<Item = B> + Clone, B: std::borrow::Borrow<chrono::format::I
     super::StringT for chrono::format::DelayedFormat<I>
 {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<BUF: super::BufT>(self, string: &mut BUF) {
         // TODO: Avoid allocation here, though chrono doesn't provide a way to do so.
-        string.extend(self.to_string().as_bytes());
-    }
-
-    #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, _separator: &str) {
-        self.encode_to_buf(string);
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        // TODO: Avoid allocation here, though chrono doesn't provide a way to do so.
-        string.extend(self.to_string().as_bytes());
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, _separator: &str) {
-        self.encode_to_bytes_buf(string);
+        string.extend_from_slice(self.to_string().as_bytes());
     }
 }
 
 impl_for_extern_type! {
     #[cfg(feature = "feat-string-ext-http")]
     http::HeaderName: self, string => {
-        string.extend(self.as_str().as_bytes());
+        string.extend_from_slice(self.as_str().as_bytes());
+    }
+    len_hint => {
+        (self.as_str().len(), Some(self.as_str().len()))
     }
 }
 
 impl_for_extern_type! {
     #[cfg(feature = "feat-string-ext-http")]
     http::Method: self, string => {
-        string.extend(self.as_str().as_bytes());
+        string.extend_from_slice(self.as_str().as_bytes());
+    }
+    len_hint => {
+        (self.as_str().len(), Some(self.as_str().len()))
     }
 }
 
 impl_for_extern_type! {
     #[cfg(feature = "feat-string-ext-http")]
     http::StatusCode: self, string => {
-        string.extend(self.as_str().as_bytes());
+        string.extend_from_slice(self.as_str().as_bytes());
+    }
+    len_hint => {
+        (self.as_str().len(), Some(self.as_str().len()))
     }
 }
 
@@ -105,21 +90,29 @@ impl_for_extern_type! {
     #[cfg(feature = "feat-string-ext-http")]
     http::Uri: self, string => {
         if let Some(scheme) = self.scheme() {
-            string.extend(scheme.as_str().as_bytes());
-            string.extend(b"://");
+            string.extend_from_slice(scheme.as_str().as_bytes());
+            string.extend_from_slice(b"://");
         }
 
         if let Some(authority) = self.authority() {
-            string.extend(authority.as_str().as_bytes());
+            string.extend_from_slice(authority.as_str().as_bytes());
         }
 
-        string.extend(self.path().as_bytes());
+        string.extend_from_slice(self.path().as_bytes());
 
         if let Some(query) = self.query() {
-            string.extend(b"?");
-            string.extend(query.as_bytes());
+            string.push(b'?');
+            string.extend_from_slice(query.as_bytes());
         }
     }
+    len_hint => {
+        let len = self.scheme_str().map_or(0, |scheme| scheme.len() + 3)
+            + self.authority().map_or(0, |authority| authority.as_str().len())
+            + self.path().len()
+            + self.query().map_or(0, |query| query.len() + 1);
+
+        (len, Some(len))
+    }
 }
 
 impl_for_extern_type! {
@@ -132,11 +125,14 @@ impl_for_extern_type! {
             http::Version::HTTP_2 => &b"HTTP/2.0"[..],
             http::Version::HTTP_3 => &b"HTTP/3.0"[..],
             _ => {
-                string.extend(format!("{self:?}").as_bytes());
+                string.extend_from_slice(format!("{self:?}").as_bytes());
                 return;
             }
         };
 
-        string.extend(str_byte);
+        string.extend_from_slice(str_byte);
+    }
+    len_hint => {
+        (8, None)
     }
 }
@@ -2,48 +2,50 @@
 
 use std::{rc::Rc, sync::Arc};
 
-use super::{StringExtT, StringT};
+use super::{BufT, Separator, StringExtT, StringT};
 use crate::impl_for_shared_ref;
 
 pub mod iterator;
+pub mod joined;
+pub mod padded;
 pub mod string;
+pub mod truncated;
 pub mod tuple;
 
 impl StringT for () {
     #[inline]
-    fn encode_to_buf(self, _string: &mut Vec<u8>) {}
+    fn encode_to_buf<B: BufT>(self, _string: &mut B) {}
 
     #[inline]
-    fn encode_to_buf_with_separator(self, _string: &mut Vec<u8>, _separator: &str) {}
-
-    #[inline]
-    fn encode_to_bytes_buf(self, _string: &mut bytes::BytesMut) {}
+    fn encode_to_buf_with_separator<B: BufT>(
+        self,
+        _string: &mut B,
+        _separator: &mut Separator<'_>,
+    ) {
+    }
 
     #[inline]
-    fn encode_to_bytes_buf_with_separator(self, _string: &mut bytes::BytesMut, _separator: &str) {}
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(0))
+    }
 }
 
 impl StringExtT for () {}
 
 impl<T: StringT> StringT for Box<T> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         (*self).encode_to_buf(string);
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+    fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
         (*self).encode_to_buf_with_separator(string, separator);
     }
 
     #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        (*self).encode_to_bytes_buf(string);
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        (*self).encode_to_bytes_buf_with_separator(string, separator);
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (**self).len_hint()
     }
 }
 
@@ -51,30 +53,24 @@ impl<T: StringExtT> StringExtT for Box<T> {}
 
 impl<T: StringT> StringT for Option<T> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         if let Some(inner) = self {
             inner.encode_to_buf(string);
         }
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+    fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
         if let Some(inner) = self {
             inner.encode_to_buf_with_separator(string, separator);
         }
     }
 
     #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        if let Some(inner) = self {
-            inner.encode_to_bytes_buf(string);
-        }
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        if let Some(inner) = self {
-            inner.encode_to_bytes_buf_with_separator(string, separator);
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Some(inner) => inner.len_hint(),
+            None => (0, Some(0)),
         }
     }
 }
@@ -99,30 +95,24 @@ impl<T: StringExtT> StringExtT for Option<T> {
 
 impl<T: StringT, E> StringT for Result<T, E> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         if let Ok(inner) = self {
             inner.encode_to_buf(string);
         }
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+    fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
         if let Ok(inner) = self {
             inner.encode_to_buf_with_separator(string, separator);
         }
     }
 
     #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        if let Ok(inner) = self {
-            inner.encode_to_bytes_buf(string);
-        }
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        if let Ok(inner) = self {
-            inner.encode_to_bytes_buf_with_separator(string, separator);
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Ok(inner) => inner.len_hint(),
+            Err(_) => (0, Some(0)),
         }
     }
 }
@@ -24,12 +24,15 @@
 //!   We have implemented `StringT` for most `&T` where T: Copy, though best
 //!   effort.
 
-use super::{StringExtT, StringT};
-use crate::wrapper;
+use super::{BufT, Separator, StringExtT, StringT};
+use crate::{string::len_hint_add, wrapper};
 
 wrapper! {
     #[derive(Debug)]
     /// Wrapper for any iterator with items implementing `StringT`.
+    ///
+    /// Notice: since we cannot inspect the items without consuming the
+    /// iterator, the length hint is always unknown.
 
     pub IterWrapper<I>(pub I)
 }
@@ -48,30 +51,18 @@ where
     I::Item: StringT,
 {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         for item in self.inner {
             item.encode_to_buf(string);
+            string.commit();
         }
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+    fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
         for item in self.inner {
             item.encode_to_buf_with_separator(string, separator);
-        }
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        for item in self.inner {
-            item.encode_to_bytes_buf(string);
-        }
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        for item in self.inner {
-            item.encode_to_bytes_buf_with_separator(string, separator);
+            string.commit();
         }
     }
 }
@@ -103,24 +94,14 @@ where
     F: FnMut(I::Item) -> T,
 {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         str_iter_wrapper!(self.into_iter()).encode_to_buf(string);
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+    fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
         str_iter_wrapper!(self.into_iter()).encode_to_buf_with_separator(string, separator);
     }
-
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        str_iter_wrapper!(self.into_iter()).encode_to_bytes_buf(string);
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        str_iter_wrapper!(self.into_iter()).encode_to_bytes_buf_with_separator(string, separator);
-    }
 }
 
 impl<T, I, F> StringExtT for std::iter::Map<I, F>
@@ -150,25 +131,26 @@ where
 
 macro_rules! impl_for_array_or_slice_like {
     (STRING_T: $($tt:tt)*) => {
+        impl_for_array_or_slice_like!(STRING_T[|item| item.len_hint()]: $($tt)*);
+    };
+    (STRING_T_REF: $($tt:tt)*) => {
+        impl_for_array_or_slice_like!(STRING_T[|item| (&item).len_hint()]: $($tt)*);
+    };
+    (STRING_T[$len_hint:expr]: $($tt:tt)*) => {
         $($tt)* {
             #[inline]
-            fn encode_to_buf(self, string: &mut Vec<u8>) {
+            fn encode_to_buf<B: BufT>(self, string: &mut B) {
                 str_iter_wrapper!(self.into_iter()).encode_to_buf(string);
             }
 
             #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+            fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                 str_iter_wrapper!(self.into_iter()).encode_to_buf_with_separator(string, separator);
             }
 
             #[inline]
-            fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                str_iter_wrapper!(self.into_iter()).encode_to_bytes_buf(string);
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                str_iter_wrapper!(self.into_iter()).encode_to_bytes_buf_with_separator(string, separator);
+            fn len_hint(&self) -> (usize, Option<usize>) {
+                self.iter().map($len_hint).fold((0, Some(0)), len_hint_add)
             }
         }
     };
@@ -196,10 +178,10 @@ macro_rules! impl_for_array_or_slice_like {
 impl_for_array_or_slice_like!(STRING_T: impl<T, const N: usize> StringT for [T; N] where T: StringT);
 impl_for_array_or_slice_like!(STRING_EXT_T: impl<T, const N: usize> StringExtT for [T; N] where T: StringT);
 
-impl_for_array_or_slice_like!(STRING_T: impl<T, const N: usize> StringT for &[T; N] where for<'a> &'a T: StringT);
+impl_for_array_or_slice_like!(STRING_T_REF: impl<T, const N: usize> StringT for &[T; N] where for<'a> &'a T: StringT);
 impl_for_array_or_slice_like!(STRING_EXT_T: impl<T, const N: usize> StringExtT for &[T; N] where for<'a> &'a T: StringT);
 
-impl_for_array_or_slice_like!(STRING_T: impl<T> StringT for &[T] where for<'a> &'a T: StringT);
+impl_for_array_or_slice_like!(STRING_T_REF: impl<T> StringT for &[T] where for<'a> &'a T: StringT);
 impl_for_array_or_slice_like!(STRING_EXT_T: impl<T> StringExtT for &[T] where for<'a> &'a T: StringT);
 
 impl_for_array_or_slice_like!(STRING_T: impl<T> StringT for Vec<T> where T: StringT);
@@ -0,0 +1,189 @@
+//! Implementations for [`Joined`].
+//!
+//! The separator of `str_concat!(sep = ...)` is global to the whole call, and
+//! nested containers just inherit it. [`Joined`] carries its own separator
+//! (plus optional open / close delimiters), and is considered as a single item
+//! by the outer one.
+
+use super::{iterator::IterWrapper, BufT, Separator, StringExtT, StringT};
+use crate::string::len_hint_add;
+
+#[derive(Debug, Clone, Copy)]
+/// Join items of the inner value (tuple, iterator, slice, etc) with its own
+/// separator, optionally enclosed by open / close delimiters.
+///
+/// Items writing nothing (like `None`) are skipped, and nested containers
+/// (except another [`Joined`]) are flattened with the same separator.
+///
+/// # Example
+///
+/// ```rust
+/// # use macro_toolset::{str_concat, string::{general::joined::Joined, StringExtT}};
+/// assert_eq!(
+///     str_concat!(
+///         "list=",
+///         ["a", "b", "c"].joined(", ").with_delimiters("[", "]"),
+///         ";",
+///         (1u8, None::<u8>, 2u8, 3u8).joined("|").with_delimiters("(", ")"),
+///         ";",
+///         Joined::iter((1..=3u8).map(|n| n * 2), "+")
+///     ),
+///     "list=[a, b, c];(1|2|3);2+4+6"
+/// );
+/// ```
+pub struct Joined<'s, T> {
+    inner: T,
+    separator: &'s str,
+    delimiters: Option<(&'s str, &'s str)>,
+}
+
+impl<'s, T> Joined<'s, T> {
+    #[inline]
+    /// Create a new [`Joined`] with given separator and no delimiters.
+    pub const fn new(inner: T, separator: &'s str) -> Self {
+        Self {
+            inner,
+            separator,
+            delimiters: None,
+        }
+    }
+
+    #[inline]
+    /// Enclose the joined items with given open / close delimiters.
+    ///
+    /// The delimiters are always written, even when there's no item.
+    pub fn with_delimiters(mut self, open: &'s str, close: &'s str) -> Self {
+        self.delimiters = Some((open, close));
+        self
+    }
+}
+
+impl<'s, I> Joined<'s, IterWrapper<I>> {
+    #[inline]
+    /// Create a new [`Joined`] over the given iterator, see [`IterWrapper`].
+    pub fn iter<II>(iter: II, separator: &'s str) -> Self
+    where
+        II: IntoIterator<IntoIter = I>,
+    {
+        Self::new(
+            IterWrapper {
+                inner: iter.into_iter(),
+            },
+            separator,
+        )
+    }
+}
+
+impl<T: StringT> StringT for Joined<'_, T> {
+    #[inline]
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        if let Some((open, _)) = self.delimiters {
+            string.extend_from_slice(open.as_bytes());
+        }
+
+        self.inner
+            .encode_to_buf_with_separator(string, &mut Separator::new(self.separator));
+
+        if let Some((_, close)) = self.delimiters {
+            string.extend_from_slice(close.as_bytes());
+        }
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let delimiters_len = self
+            .delimiters
+            .map_or(0, |(open, close)| open.len() + close.len());
+
+        let (lower, upper) = len_hint_add(
+            self.inner.len_hint(),
+            (delimiters_len, Some(delimiters_len)),
+        );
+
+        // The number of items is unknown, so are the separators.
+        (lower, upper.filter(|_| self.separator.is_empty()))
+    }
+}
+
+impl<T: StringT> StringExtT for Joined<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::str_concat;
+
+    #[test]
+    fn test_joined() {
+        assert_eq!(vec!["a", "b"].joined(", ").to_string_ext(), "a, b");
+        assert_eq!(
+            ("a", 1u8, 'c')
+                .joined("|")
+                .with_delimiters("(", ")")
+                .to_string_ext(),
+            "(a|1|c)"
+        );
+        assert_eq!(
+            Joined::iter(["x", "", "y"].iter(), "-").to_string_ext(),
+            "x-y"
+        );
+
+        // Empty.
+        assert_eq!(Vec::<&str>::new().joined(",").to_string_ext(), "");
+        assert_eq!(
+            Vec::<&str>::new()
+                .joined(",")
+                .with_delimiters("[", "]")
+                .to_string_ext(),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn test_joined_nested() {
+        // Inside a non-separated concat.
+        assert_eq!(
+            str_concat!("a=", ["1", "2"].joined(","), "&b=", ["3"].joined(",")),
+            "a=1,2&b=3"
+        );
+
+        // Inside a separated concat, the separator is not inherited.
+        assert_eq!(
+            str_concat!(sep = " "; "x", ["1", "2"].joined(",").with_delimiters("[", "]"), None::<&str>, "y"),
+            "x [1,2] y"
+        );
+
+        // Nested `Joined`, while other containers are flattened.
+        assert_eq!(
+            (
+                ["a", "b"].joined("+"),
+                ("c", vec!["d", "e"]),
+                ["f"].joined("+").with_delimiters("{", "}"),
+            )
+                .joined(", ")
+                .with_delimiters("[", "]")
+                .to_string_ext(),
+            "[a+b, c, d, e, {f}]"
+        );
+
+        // With prefix / suffix.
+        assert_eq!(
+            ["a", "b"]
+                .joined(",")
+                .with_prefix("<")
+                .with_suffix(">")
+                .to_string_ext(),
+            "<a,b>"
+        );
+    }
+
+    #[test]
+    fn test_joined_len_hint() {
+        let joined = ["ab", "c"].joined(", ").with_delimiters("[", "]");
+        assert_eq!(joined.len_hint().0, 5);
+        assert_eq!(joined.len_hint().1, None);
+        assert_eq!(joined.to_string_ext().len(), 7);
+
+        let joined = ("ab", 'c').joined("").with_delimiters("[", "]");
+        assert_eq!(joined.len_hint(), (5, Some(5)));
+    }
+}
@@ -0,0 +1,386 @@
+//! Implementations for [`Padded`].
+
+pub use std::fmt::Alignment;
+
+use super::{BufT, StringExtT, StringT};
+use crate::string::{len_hint_add, Uncommitted};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How the width of [`Padded`] is counted.
+pub enum WidthUnit {
+    #[default]
+    /// Unicode scalar values, i.e. [`char`]s.
+    Chars,
+
+    /// Display columns in a monospace terminal, e.g. CJK characters and most
+    /// emojis take 2 columns, while combining marks take none.
+    ///
+    /// This is a simplified version of [UAX #11](https://www.unicode.org/reports/tr11/).
+    Columns,
+}
+
+impl WidthUnit {
+    #[inline]
+    /// Returns the width of the given UTF-8 bytes.
+    fn width_of(self, bytes: &[u8]) -> usize {
+        match self {
+            // Just count the non-continuation bytes.
+            Self::Chars => bytes.iter().filter(|&&b| (b as i8) >= -0x40).count(),
+            Self::Columns => std::str::from_utf8(bytes)
+                .map_or(bytes.len(), |s| s.chars().map(char_columns).sum()),
+        }
+    }
+
+    #[inline]
+    /// Returns the width of the given char, at least 1.
+    fn width_of_char(self, c: char) -> usize {
+        match self {
+            Self::Chars => 1,
+            Self::Columns => char_columns(c).max(1),
+        }
+    }
+}
+
+/// Returns the display columns of the given char.
+const fn char_columns(c: char) -> usize {
+    match c as u32 {
+        // Control characters, combining marks, zero width characters and
+        // variation selectors.
+        0x00..=0x1F
+        | 0x7F..=0x9F
+        | 0x0300..=0x036F
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x2028..=0x202E
+        | 0x2060..=0x2064
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F
+        | 0xFEFF
+        | 0xE0100..=0xE01EF => 0,
+        // East Asian Wide and Fullwidth characters.
+        0x1100..=0x115F
+        | 0x231A..=0x231B
+        | 0x2329..=0x232A
+        | 0x23E9..=0x23EC
+        | 0x23F0
+        | 0x23F3
+        | 0x25FD..=0x25FE
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x26AA..=0x26AB
+        | 0x26BD..=0x26BE
+        | 0x26C4..=0x26C5
+        | 0x26F2..=0x26F5
+        | 0x26FA
+        | 0x26FD
+        | 0x2705
+        | 0x270A..=0x270B
+        | 0x2728
+        | 0x274C
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27B0
+        | 0x27BF
+        | 0x2B1B..=0x2B1C
+        | 0x2B50
+        | 0x2B55
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xA960..=0xA97F
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE10..=0xFE19
+        | 0xFE30..=0xFE6F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x16FE0..=0x16FE4
+        | 0x17000..=0x18CFF
+        | 0x1B000..=0x1B2FF
+        | 0x1F004
+        | 0x1F0CF
+        | 0x1F18E
+        | 0x1F191..=0x1F19A
+        | 0x1F200..=0x1F251
+        | 0x1F300..=0x1F320
+        | 0x1F32D..=0x1F335
+        | 0x1F337..=0x1F37C
+        | 0x1F37E..=0x1F393
+        | 0x1F3A0..=0x1F3CA
+        | 0x1F3CF..=0x1F3D3
+        | 0x1F3E0..=0x1F3F0
+        | 0x1F3F4
+        | 0x1F3F8..=0x1F43E
+        | 0x1F440
+        | 0x1F442..=0x1F4FC
+        | 0x1F4FF..=0x1F53D
+        | 0x1F54B..=0x1F54E
+        | 0x1F550..=0x1F567
+        | 0x1F57A
+        | 0x1F595..=0x1F596
+        | 0x1F5A4
+        | 0x1F5FB..=0x1F64F
+        | 0x1F680..=0x1F6C5
+        | 0x1F6CC
+        | 0x1F6D0..=0x1F6D2
+        | 0x1F6D5..=0x1F6D7
+        | 0x1F6DC..=0x1F6DF
+        | 0x1F6EB..=0x1F6EC
+        | 0x1F6F4..=0x1F6FC
+        | 0x1F7E0..=0x1F7EB
+        | 0x1F7F0
+        | 0x1F90C..=0x1F93A
+        | 0x1F93C..=0x1F945
+        | 0x1F947..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Pad the inner value to the given width, with given alignment and fill
+/// char, like `format!("{:*^10}", ...)` but for any [`StringT`].
+///
+/// By default, the value is left-aligned and padded with spaces, and the width
+/// is counted in [`WidthUnit::Chars`]. Values wider than the width are written
+/// as is.
+///
+/// # Example
+///
+/// ```rust
+/// # use macro_toolset::{str_concat, string::{general::padded::{Alignment, WidthUnit}, StringExtT}};
+/// assert_eq!(
+///     str_concat!(
+///         "|", "name".padded(6),
+///         "|", 42u8.padded(5).align(Alignment::Right),
+///         "|", ("a", 'b').padded(6).align(Alignment::Center).fill('*'),
+///         "|"
+///     ),
+///     "|name  |   42|**ab**|"
+/// );
+/// assert_eq!("中文".padded(6).unit(WidthUnit::Columns).to_string_ext(), "中文  ");
+/// ```
+pub struct Padded<T> {
+    inner: T,
+    width: usize,
+    align: Alignment,
+    fill: char,
+    unit: WidthUnit,
+}
+
+impl<T> Padded<T> {
+    #[inline]
+    /// Create a new [`Padded`] with given width, left-aligned and padded with
+    /// spaces.
+    pub const fn new(inner: T, width: usize) -> Self {
+        Self {
+            inner,
+            width,
+            align: Alignment::Left,
+            fill: ' ',
+            unit: WidthUnit::Chars,
+        }
+    }
+
+    #[inline]
+    /// Set the alignment.
+    pub const fn align(mut self, align: Alignment) -> Self {
+        self.align = align;
+        self
+    }
+
+    #[inline]
+    /// Set the fill char.
+    pub const fn fill(mut self, fill: char) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    #[inline]
+    /// Set how the width is counted.
+    pub const fn unit(mut self, unit: WidthUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+}
+
+impl<T: StringT> StringT for Padded<T> {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        let start = string.len();
+
+        self.inner.encode_to_buf(&mut Uncommitted(&mut *string));
+
+        let end = string.len();
+
+        let Some(remaining) = string
+            .as_mut_slice()
+            .get(start..end)
+            .and_then(|written| self.width.checked_sub(self.unit.width_of(written)))
+        else {
+            return;
+        };
+
+        let fill_count = remaining / self.unit.width_of_char(self.fill);
+
+        if fill_count == 0 {
+            return;
+        }
+
+        let (left_count, right_count) = match self.align {
+            Alignment::Left => (0, fill_count),
+            Alignment::Right => (fill_count, 0),
+            Alignment::Center => (fill_count / 2, fill_count - fill_count / 2),
+        };
+
+        let mut fill = [0; 4];
+        let fill = self.fill.encode_utf8(&mut fill).as_bytes();
+
+        string.resize(end + fill_count * fill.len(), 0);
+
+        let Some(buf) = string.as_mut_slice().get_mut(start..) else {
+            return;
+        };
+        let value_len = end - start;
+        let left_len = left_count * fill.len();
+
+        // Move the value right, then fill both sides.
+        buf.copy_within(..value_len, left_len);
+
+        let (left, rest) = buf.split_at_mut(left_len);
+        left.chunks_exact_mut(fill.len())
+            .chain(rest[value_len..].chunks_exact_mut(fill.len()))
+            .for_each(|chunk| chunk.copy_from_slice(fill));
+
+        debug_assert_eq!(
+            buf.len(),
+            (left_count + right_count) * fill.len() + value_len
+        );
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.len_hint();
+        let max_fill_len = self.width * self.fill.len_utf8();
+
+        (lower, len_hint_add((0, upper), (0, Some(max_fill_len))).1)
+    }
+}
+
+impl<T: StringT> StringExtT for Padded<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        str_concat,
+        string::{general::iterator::IterWrapper, NumStr, PushAnyT},
+    };
+
+    #[test]
+    fn test_padded() {
+        assert_eq!("ab".padded(5).to_string_ext(), "ab   ");
+        assert_eq!(
+            "ab".padded(5).align(Alignment::Right).to_string_ext(),
+            "   ab"
+        );
+        assert_eq!(
+            "ab".padded(5).align(Alignment::Center).to_string_ext(),
+            " ab  "
+        );
+        assert_eq!("abcdef".padded(3).to_string_ext(), "abcdef");
+        assert_eq!("".padded(2).fill('-').to_string_ext(), "--");
+
+        // Multi-byte fill char, width in chars.
+        assert_eq!(
+            "😀"
+                .padded(3)
+                .fill('─')
+                .align(Alignment::Center)
+                .to_string_ext(),
+            "─😀─"
+        );
+    }
+
+    #[test]
+    fn test_padded_columns() {
+        assert_eq!("中文".padded(4).to_string_ext(), "中文  ");
+        assert_eq!(
+            "中文".padded(5).unit(WidthUnit::Columns).to_string_ext(),
+            "中文 "
+        );
+        assert_eq!(
+            "e\u{301}"
+                .padded(2)
+                .unit(WidthUnit::Columns)
+                .to_string_ext(),
+            "e\u{301} "
+        );
+
+        // Wide fill char.
+        assert_eq!(
+            "a".padded(6)
+                .fill('中')
+                .unit(WidthUnit::Columns)
+                .align(Alignment::Right)
+                .to_string_ext(),
+            "中中a"
+        );
+    }
+
+    #[test]
+    fn test_padded_nested() {
+        assert_eq!(
+            str_concat!(
+                "[",
+                (NumStr::hex_default(0xabu8), "-", 1u8)
+                    .padded(8)
+                    .align(Alignment::Right)
+                    .fill('.'),
+                "]"
+            ),
+            "[....ab-1]"
+        );
+
+        assert_eq!(
+            str_concat!(sep = "|"; "a".padded(3), None::<&str>, 12u16.padded(4).align(Alignment::Right)),
+            "a  |  12"
+        );
+
+        // Existing content is not counted.
+        let mut string = "xyz".to_string();
+        string.push_any("a".padded(3).align(Alignment::Center));
+        assert_eq!(string, "xyz a ");
+    }
+
+    #[test]
+    fn test_padded_write_to_fmt() {
+        let mut string = String::new();
+        "x".padded(20000).write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, "x".padded(20000).to_string_ext());
+        assert_eq!(string.len(), 20000);
+
+        // Inner value committing items.
+        let value = || {
+            IterWrapper {
+                inner: (0..3000u32).map(|n| (n, ' ')),
+            }
+            .padded(20000)
+            .align(Alignment::Center)
+            .fill('*')
+        };
+        let mut string = String::new();
+        value().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, value().to_string_ext());
+    }
+}
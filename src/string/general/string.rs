@@ -18,7 +18,7 @@
 
 use std::{borrow::Cow, ops::Deref, rc::Rc, sync::Arc};
 
-use super::{StringExtT, StringT};
+use super::{BufT, Separator, StringExtT, StringT};
 use crate::{impl_for_shared_ref, impl_for_wrapper, wrapper};
 
 macro_rules! impl_for_string {
@@ -39,25 +39,13 @@ macro_rules! impl_for_string {
     (@INTERNAL $ty:ty) => {
         impl StringT for $ty {
             #[inline]
-            fn encode_to_buf(self, string: &mut Vec<u8>) {
-                string.extend(self.as_bytes());
+            fn encode_to_buf<B: BufT>(self, string: &mut B) {
+                string.extend_from_slice(self.as_bytes());
             }
 
             #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-                string.extend(self.as_bytes());
-                string.extend(separator.as_bytes());
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                string.extend(self.as_bytes());
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                string.extend(self.as_bytes());
-                string.extend(separator.as_bytes());
+            fn len_hint(&self) -> (usize, Option<usize>) {
+                (self.len(), Some(self.len()))
             }
         }
 
@@ -76,32 +64,17 @@ impl_for_string! {
 
 impl StringT for char {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         // ! '�' != "�" in utf8 world. see [`String::push`].
         match self.len_utf8() {
             1 => string.push(self as u8),
-            _ => string.extend(self.encode_utf8(&mut [0; 4]).as_bytes()),
+            _ => string.extend_from_slice(self.encode_utf8(&mut [0; 4]).as_bytes()),
         }
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-        self.encode_to_buf(string);
-        string.extend(separator.as_bytes());
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        // ! '�' != "�" in utf8 world. see [`String::push`].
-        match self.len_utf8() {
-            1 => string.extend([self as u8]),
-            _ => string.extend(self.encode_utf8(&mut [0; 4]).as_bytes()),
-        }
-    }
-
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        self.encode_to_bytes_buf(string);
-        string.extend(separator.as_bytes());
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (self.len_utf8(), Some(self.len_utf8()))
     }
 }
 
@@ -0,0 +1,263 @@
+//! Implementations for [`Truncated`].
+
+use super::{BufT, StringExtT, StringT};
+use crate::string::Uncommitted;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How the maximum length of [`Truncated`] is counted.
+pub enum LengthUnit {
+    #[default]
+    /// Bytes (of the UTF-8 encoded string).
+    Bytes,
+
+    /// Unicode scalar values, i.e. [`char`]s.
+    Chars,
+}
+
+impl LengthUnit {
+    #[inline]
+    /// Returns the byte index where the UTF-8 bytes should be cut to keep at
+    /// most `max_len` units, or `None` if no need to cut.
+    fn cut_index(self, bytes: &[u8], max_len: usize) -> Option<usize> {
+        match self {
+            Self::Bytes => {
+                if bytes.len() <= max_len {
+                    return None;
+                }
+
+                // Never split a UTF-8 sequence.
+                (0..=max_len)
+                    .rev()
+                    .find(|&idx| is_char_boundary(bytes[idx]))
+            }
+            Self::Chars => bytes
+                .iter()
+                .enumerate()
+                .filter(|(_, &b)| is_char_boundary(b))
+                .nth(max_len)
+                .map(|(idx, _)| idx),
+        }
+    }
+
+    #[inline]
+    /// Returns the length of the given string.
+    fn len_of(self, s: &str) -> usize {
+        match self {
+            Self::Bytes => s.len(),
+            Self::Chars => s.chars().count(),
+        }
+    }
+}
+
+#[inline]
+/// Returns `true` if the byte is not a UTF-8 continuation byte.
+const fn is_char_boundary(b: u8) -> bool {
+    (b as i8) >= -0x40
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Limit the output of the inner value to the given length, never splitting a
+/// UTF-8 sequence, optionally with an ellipsis marker appended when truncated.
+///
+/// The ellipsis is counted in the maximum length, and is omitted if it does
+/// not fit in at all.
+///
+/// By default, the length is counted in [`LengthUnit::Bytes`].
+///
+/// # Example
+///
+/// ```rust
+/// # use macro_toolset::string::{general::truncated::LengthUnit, StringExtT};
+/// assert_eq!("hello world".truncated(8).to_string_ext(), "hello wo");
+/// assert_eq!("hello world".truncated(8).with_ellipsis("...").to_string_ext(), "hello...");
+/// assert_eq!("你好世界".truncated(7).to_string_ext(), "你好");
+/// assert_eq!(
+///     "你好世界".truncated(3).unit(LengthUnit::Chars).with_ellipsis("…").to_string_ext(),
+///     "你好…"
+/// );
+/// ```
+pub struct Truncated<'e, T> {
+    inner: T,
+    max_len: usize,
+    unit: LengthUnit,
+    ellipsis: &'e str,
+}
+
+impl<T> Truncated<'static, T> {
+    #[inline]
+    /// Create a new [`Truncated`] with given maximum length in bytes, without
+    /// ellipsis.
+    pub const fn new(inner: T, max_len: usize) -> Self {
+        Self {
+            inner,
+            max_len,
+            unit: LengthUnit::Bytes,
+            ellipsis: "",
+        }
+    }
+}
+
+impl<'e, T> Truncated<'e, T> {
+    #[inline]
+    /// Set how the maximum length is counted.
+    pub const fn unit(mut self, unit: LengthUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    #[inline]
+    /// Set the ellipsis marker appended when truncated, like `...` or `…`.
+    pub fn with_ellipsis<'n>(self, ellipsis: &'n str) -> Truncated<'n, T> {
+        Truncated {
+            inner: self.inner,
+            max_len: self.max_len,
+            unit: self.unit,
+            ellipsis,
+        }
+    }
+}
+
+impl<T: StringT> StringT for Truncated<'_, T> {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        let start = string.len();
+
+        self.inner.encode_to_buf(&mut Uncommitted(&mut *string));
+
+        let Some(written) = string.as_mut_slice().get(start..) else {
+            return;
+        };
+
+        if self.unit.cut_index(written, self.max_len).is_none() {
+            return;
+        }
+
+        let ellipsis_len = self.unit.len_of(self.ellipsis);
+
+        let (keep_len, ellipsis) = match self.max_len.checked_sub(ellipsis_len) {
+            Some(keep_len) => (keep_len, self.ellipsis),
+            None => (self.max_len, ""),
+        };
+
+        let cut_index = self
+            .unit
+            .cut_index(written, keep_len)
+            .unwrap_or(written.len());
+
+        string.truncate(start + cut_index);
+        string.extend_from_slice(ellipsis.as_bytes());
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let max_bytes = match self.unit {
+            LengthUnit::Bytes => Some(self.max_len),
+            LengthUnit::Chars => self.max_len.checked_mul(4),
+        };
+
+        let upper = match (self.inner.len_hint().1, max_bytes) {
+            (Some(upper), Some(max_bytes)) => Some(upper.min(max_bytes)),
+            (upper, max_bytes) => upper.or(max_bytes),
+        };
+
+        (0, upper)
+    }
+}
+
+impl<T: StringT> StringExtT for Truncated<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        str_concat,
+        string::{general::iterator::IterWrapper, PushAnyT},
+    };
+
+    #[test]
+    fn test_truncated_bytes() {
+        assert_eq!("abc".truncated(3).to_string_ext(), "abc");
+        assert_eq!("abcd".truncated(3).to_string_ext(), "abc");
+        assert_eq!("abcd".truncated(0).to_string_ext(), "");
+        assert_eq!("ab😀".truncated(5).to_string_ext(), "ab");
+        assert_eq!("ab😀".truncated(6).to_string_ext(), "ab😀");
+
+        assert_eq!(
+            "abcdef".truncated(5).with_ellipsis("..").to_string_ext(),
+            "abc.."
+        );
+        assert_eq!(
+            "abcde".truncated(5).with_ellipsis("..").to_string_ext(),
+            "abcde"
+        );
+        assert_eq!(
+            "a😀bcd".truncated(6).with_ellipsis("..").to_string_ext(),
+            "a.."
+        );
+        // Ellipsis does not fit.
+        assert_eq!(
+            "abcdef".truncated(1).with_ellipsis("..").to_string_ext(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_truncated_chars() {
+        let truncated = |s: &'static str, n| s.truncated(n).unit(LengthUnit::Chars);
+
+        assert_eq!(truncated("你好", 2).to_string_ext(), "你好");
+        assert_eq!(truncated("你好世界", 2).to_string_ext(), "你好");
+        assert_eq!(truncated("你好世界", 0).to_string_ext(), "");
+        assert_eq!(
+            truncated("你好世界", 3).with_ellipsis("…").to_string_ext(),
+            "你好…"
+        );
+        assert_eq!(
+            truncated("你好世", 3).with_ellipsis("…").to_string_ext(),
+            "你好世"
+        );
+    }
+
+    #[test]
+    fn test_truncated_nested() {
+        assert_eq!(
+            str_concat!("[", ("user-", 1234567u32).truncated(8), "]"),
+            "[user-123]"
+        );
+        assert_eq!(
+            str_concat!(sep = ","; "abc".truncated(0), "def".truncated(2)),
+            "de"
+        );
+
+        // Consistent across targets, existing content is untouched.
+        let mut vec = b"head:".to_vec();
+        vec.push_any("你好世界".truncated(8).with_ellipsis("."));
+        let mut bytes = bytes::BytesMut::from(&b"head:"[..]);
+        bytes.push_any("你好世界".truncated(8).with_ellipsis("."));
+        assert_eq!(vec, "head:你好.".as_bytes());
+        assert_eq!(&bytes[..], &vec[..]);
+    }
+
+    #[test]
+    fn test_truncated_write_to_fmt() {
+        let value = || {
+            IterWrapper {
+                inner: (0..3000u32).map(|n| (n, ' ')),
+            }
+            .truncated(10)
+            .with_ellipsis("...")
+        };
+        let mut string = String::new();
+        value().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, value().to_string_ext());
+        assert_eq!(string, "0 1 2 3...");
+
+        let value = "你".repeat(2000);
+        let mut string = String::new();
+        (&value)
+            .truncated(5000)
+            .unit(LengthUnit::Bytes)
+            .write_to_fmt(&mut string)
+            .unwrap();
+        assert_eq!(string, "你".repeat(1666));
+    }
+}
@@ -3,8 +3,8 @@
 //! Since array-or-slice-like types can only carry  elements of the same type,
 //! tuple is the only way to `push` multiple elements with different types.
 
-use super::{StringExtT, StringT};
-use crate::wrapper;
+use super::{BufT, Separator, StringExtT, StringT};
+use crate::{string::len_hint_add, wrapper};
 
 wrapper! {
     #[derive(Debug, Clone, Copy)]
@@ -17,35 +17,31 @@ macro_rules! impl_for_tuple {
         #[allow(non_snake_case)]
         impl<$($name: StringT),+> StringT for ($($name,)+) {
             #[inline]
-            fn encode_to_buf(self, string: &mut Vec<u8>) {
+            fn encode_to_buf<B: BufT>(self, string: &mut B) {
                 let ($($name,)+) = self;
                 $(
                     $name.encode_to_buf(string);
+                    string.commit();
                 )+
             }
 
             #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+            fn encode_to_buf_with_separator<B: BufT>(self, string: &mut B, separator: &mut Separator<'_>) {
                 let ($($name,)+) = self;
                 $(
                     $name.encode_to_buf_with_separator(string, separator);
+                    string.commit();
                 )+
             }
 
             #[inline]
-            fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
+            fn len_hint(&self) -> (usize, Option<usize>) {
                 let ($($name,)+) = self;
+                let hint = (0, Some(0));
                 $(
-                    $name.encode_to_bytes_buf(string);
-                )+
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                let ($($name,)+) = self;
-                $(
-                    $name.encode_to_bytes_buf_with_separator(string, separator);
+                    let hint = len_hint_add(hint, $name.len_hint());
                 )+
+                hint
             }
         }
 
@@ -81,25 +77,13 @@ macro_rules! impl_for_tuple {
         #[allow(non_snake_case)]
         impl<$($name: StringT),+> StringT for SeplessTuple<($($name,)+)> {
             #[inline]
-            fn encode_to_buf(self, string: &mut Vec<u8>) {
+            fn encode_to_buf<B: BufT>(self, string: &mut B) {
                 self.inner.encode_to_buf(string);
             }
 
             #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-                self.inner.encode_to_buf(string);
-                string.extend(separator.as_bytes());
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                self.inner.encode_to_bytes_buf(string);
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                self.inner.encode_to_bytes_buf(string);
-                string.extend(separator.as_bytes());
+            fn len_hint(&self) -> (usize, Option<usize>) {
+                self.inner.len_hint()
             }
         }
 
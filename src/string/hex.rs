@@ -3,7 +3,7 @@
 //! Actually just with [`NumStr`](crate::string::NumStr) you can do so.
 //! However for fixed length hex string, [`const_hex`] does better.
 
-use super::{StringExtT, StringT};
+use super::{BufT, StringExtT, StringT};
 
 #[derive(Debug, Clone)]
 /// Hex string with fixed length.
@@ -57,10 +57,7 @@ impl<'s, const N: usize, const P: bool, const U: bool> HexStr<'s, N, P, U> {
 
     #[inline]
     /// Encode to string
-    fn encode<T>(&self, string: &mut T)
-    where
-        T: for<'a> Extend<&'a u8>,
-    {
+    fn encode<B: BufT>(&self, string: &mut B) {
         let mut buffer = [0; N];
 
         for (idx, &i) in (0..N).rev().zip(
@@ -75,13 +72,13 @@ impl<'s, const N: usize, const P: bool, const U: bool> HexStr<'s, N, P, U> {
         }
 
         if U {
-            string.extend(
+            string.extend_from_slice(
                 const_hex::Buffer::<N, P>::new()
                     .const_format_upper(&buffer)
                     .as_bytes(),
             );
         } else {
-            string.extend(
+            string.extend_from_slice(
                 const_hex::Buffer::<N, P>::new()
                     .const_format(&buffer)
                     .as_bytes(),
@@ -92,25 +89,15 @@ impl<'s, const N: usize, const P: bool, const U: bool> HexStr<'s, N, P, U> {
 
 impl<const N: usize, const P: bool, const U: bool> StringT for HexStr<'_, N, P, U> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         self.encode(string);
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-        self.encode(string);
-        string.extend(separator.as_bytes());
-    }
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let len = N * 2 + usize::from(P) * 2;
 
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        self.encode(string);
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        self.encode(string);
-        string.extend(separator.as_bytes());
+        (len, Some(len))
     }
 }
 
@@ -118,25 +105,13 @@ impl<const N: usize, const P: bool, const U: bool> StringExtT for HexStr<'_, N,
 
 impl<const N: usize, const P: bool> StringT for const_hex::Buffer<N, P> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
-        string.extend(self.as_bytes());
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        string.extend_from_slice(self.as_bytes());
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-        string.extend(self.as_bytes());
-        string.extend(separator.as_bytes());
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        string.extend(self.as_bytes());
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        string.extend(self.as_bytes());
-        string.extend(separator.as_bytes());
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (self.as_str().len(), Some(self.as_str().len()))
     }
 }
 
@@ -218,4 +193,15 @@ mod test {
             "0x0000010203040506A7"
         );
     }
+
+    #[test]
+    fn test_len_hint() {
+        use crate::string::StringT;
+
+        let hex = HexStr::<9>::new(&[0x00, 0x01]);
+        assert_eq!(hex.len_hint(), (18, Some(18)));
+        let hex = hex.set_with_prefix::<true>();
+        assert_eq!(hex.len_hint(), (20, Some(20)));
+        assert_eq!(hex.to_string_ext().len(), 20);
+    }
 }
@@ -0,0 +1,156 @@
+//! HTML escaped string
+
+use super::{BufT, StringExtT, StringT, Uncommitted};
+use crate::wrapper;
+
+#[macro_export]
+/// See [`Escape`] for more information.
+///
+/// # Example
+///
+/// ```
+/// # use macro_toolset::{html_escape_str, string::StringExtT};
+/// let data = html_escape_str!("<a href='x'>&</a>").to_string_ext();
+/// assert_eq!(data, "&lt;a href=&#39;x&#39;&gt;&amp;&lt;/a&gt;");
+/// ```
+macro_rules! html_escape_str {
+    ($data:expr) => {
+        $crate::string::html::Escape::new($data)
+    };
+}
+
+wrapper! {
+    #[derive(Debug, Clone, Copy)]
+    /// `string` which is to be HTML escaped, `&`, `<`, `>`, `"` and `'` are
+    /// escaped, so that the output is safe in both HTML text and attribute
+    /// values quoted by either `"` or `'`.
+    ///
+    /// The inner value is written to the target buffer directly and escaped in
+    /// place, no intermediate buffer is allocated.
+    pub Escape<T>(pub T)
+}
+
+/// Returns the escaped form of the byte, or `None` if it needs no escaping.
+const fn escape(byte: u8) -> Option<&'static [u8]> {
+    match byte {
+        b'&' => Some(b"&amp;"),
+        b'<' => Some(b"&lt;"),
+        b'>' => Some(b"&gt;"),
+        b'"' => Some(b"&quot;"),
+        b'\'' => Some(b"&#39;"),
+        _ => None,
+    }
+}
+
+impl<T: StringT> StringT for Escape<T> {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        let start = string.len();
+
+        self.inner.encode_to_buf(&mut Uncommitted(&mut *string));
+
+        let end = string.len();
+
+        let Some(written) = string.as_mut_slice().get(start..end) else {
+            return;
+        };
+
+        let extra_len: usize = written
+            .iter()
+            .map(|&byte| escape(byte).map_or(0, |escaped| escaped.len() - 1))
+            .sum();
+
+        if extra_len == 0 {
+            return;
+        }
+
+        string.resize(end + extra_len, 0);
+
+        // Escape from back to front in place.
+        let Some(buf) = string.as_mut_slice().get_mut(..end + extra_len) else {
+            return;
+        };
+        let mut read = end;
+        let mut write = end + extra_len;
+
+        while read < write {
+            read -= 1;
+
+            match escape(buf[read]) {
+                Some(escaped) => {
+                    write -= escaped.len();
+                    buf[write..write + escaped.len()].copy_from_slice(escaped);
+                }
+                None => {
+                    write -= 1;
+                    buf[write] = buf[read];
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.len_hint();
+
+        // Each byte will be escaped to at most 6 bytes.
+        (lower, upper.and_then(|upper| upper.checked_mul(6)))
+    }
+}
+
+impl<T: StringT> StringExtT for Escape<T> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        str_concat,
+        string::{general::iterator::IterWrapper, PushAnyT},
+    };
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(html_escape_str!("").to_string_ext(), "");
+        assert_eq!(html_escape_str!("hello").to_string_ext(), "hello");
+        assert_eq!(
+            html_escape_str!("<p class=\"a\">Tom & 'Jerry'</p>").to_string_ext(),
+            "&lt;p class=&quot;a&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/p&gt;"
+        );
+        assert_eq!(html_escape_str!("&&").to_string_ext(), "&amp;&amp;");
+        assert_eq!(
+            html_escape_str!("你好<😀>").to_string_ext(),
+            "你好&lt;😀&gt;"
+        );
+    }
+
+    #[test]
+    fn test_escape_nested() {
+        assert_eq!(
+            str_concat!(
+                "<a title=\"",
+                html_escape_str!(("\"", 1u8, Some('&'))),
+                "\">",
+                html_escape_str!(["<", ">"]),
+                "</a>"
+            ),
+            "<a title=\"&quot;1&amp;\">&lt;&gt;</a>"
+        );
+
+        // Existing content is untouched.
+        let mut string = "<&>".to_string();
+        string.push_any(html_escape_str!("<&>"));
+        assert_eq!(string, "<&>&lt;&amp;&gt;");
+    }
+
+    #[test]
+    fn test_escape_write_to_io() {
+        let value = || {
+            html_escape_str!(IterWrapper {
+                inner: (0..2000u32).map(|n| (n, "<&>")),
+            })
+        };
+        let mut bytes = Vec::new();
+        value().write_to_io(&mut bytes).unwrap();
+        assert_eq!(bytes, value().to_string_ext().into_bytes());
+        assert!(!bytes.contains(&b'<'));
+    }
+}
@@ -0,0 +1,700 @@
+//! JSON string utilities.
+//!
+//! - [`JsonStr`]: escape any [`StringT`] as a JSON string literal.
+//! - [`json_obj!`](crate::json_obj) and [`json_arr!`](crate::json_arr): build
+//!   JSON objects and arrays, written directly into the target buffer.
+
+use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+use super::{general::iterator::IterWrapper, BufT, StringExtT, StringT, Uncommitted};
+
+#[macro_export]
+/// Build a JSON object, see [`JsonObject`](crate::string::json::JsonObject).
+///
+/// Keys can be any [`StringT`](crate::string::StringT) (a single token tree,
+/// wrap complex expressions in parentheses) and will be escaped, values can be
+/// any [`JsonValueT`](crate::string::json::JsonValueT).
+///
+/// # Example
+///
+/// ```
+/// # use macro_toolset::{json_arr, json_obj, string::StringExtT};
+/// let name = "Tom \"Cat\"";
+/// let tags = vec!["a", "b"];
+/// let data = json_obj! {
+///     "id": 1u64,
+///     "name": name,
+///     "tags": tags,
+///     "email": None::<&str>,
+///     "extra": json_obj! { "score": 9.5f32, "flags": json_arr![true, 0u8] },
+/// };
+/// assert_eq!(
+///     data.to_string_ext(),
+///     r#"{"id":1,"name":"Tom \"Cat\"","tags":["a","b"],"email":null,"extra":{"score":9.5,"flags":[true,0]}}"#
+/// );
+/// ```
+macro_rules! json_obj {
+    (@FIELDS) => {
+        ()
+    };
+    (@FIELDS $key:tt : $value:expr $(, $rest_key:tt : $rest_value:expr)*) => {
+        (($key, $value), $crate::json_obj!(@FIELDS $($rest_key : $rest_value),*))
+    };
+    ($($key:tt : $value:expr),* $(,)?) => {
+        $crate::string::json::JsonObject::new($crate::json_obj!(@FIELDS $($key : $value),*))
+    };
+}
+
+#[macro_export]
+/// Build a JSON array, see [`JsonArray`](crate::string::json::JsonArray).
+///
+/// Elements can be any [`JsonValueT`](crate::string::json::JsonValueT), and
+/// need not be of the same type.
+///
+/// # Example
+///
+/// ```
+/// # use macro_toolset::{json_arr, json_obj, string::StringExtT};
+/// assert_eq!(
+///     json_arr![1i8, "a\n", Some('b'), None::<u8>, json_obj! {}, json_arr![]].to_string_ext(),
+///     r#"[1,"a\n","b",null,{},[]]"#
+/// );
+/// ```
+macro_rules! json_arr {
+    (@ELEMENTS) => {
+        ()
+    };
+    (@ELEMENTS $value:expr $(, $rest:expr)*) => {
+        ($value, $crate::json_arr!(@ELEMENTS $($rest),*))
+    };
+    ($($value:expr),* $(,)?) => {
+        $crate::string::json::JsonArray::new($crate::json_arr!(@ELEMENTS $($value),*))
+    };
+}
+
+const HEX_CHARS_LOWER: &[u8; 16] = b"0123456789abcdef";
+
+#[derive(Debug, Clone, Copy)]
+/// JSON string literal, escaped from any inner [`StringT`].
+///
+/// `"` and `\` are escaped, control characters are escaped as `\n`, `\t`,
+/// etc or `\u00XX`.
+///
+/// # Generic
+///
+/// - Q: With the surrounding quotes, default true.
+/// - S: Escape `/` as `\/` (useful when embedding in HTML `<script>`), default
+///   false.
+/// - A: Escape non-ASCII characters as `\uXXXX` (surrogate pairs if needed),
+///   default false.
+///
+/// The inner value is written to the target buffer directly and escaped in
+/// place, no intermediate buffer is allocated.
+///
+/// # Example
+///
+/// ```rust
+/// # use macro_toolset::string::{JsonStr, StringExtT};
+/// assert_eq!(JsonStr::new("a\"b\n").to_string_ext(), r#""a\"b\n""#);
+/// assert_eq!(
+///     JsonStr::new("</你>").set_quoted::<false>().set_escape_slash::<true>().to_string_ext(),
+///     r#"<\/你>"#
+/// );
+/// assert_eq!(
+///     JsonStr::new("你😀").set_ascii_only::<true>().to_string_ext(),
+///     r#""\u4f60\ud83d\ude00""#
+/// );
+/// ```
+pub struct JsonStr<T, const Q: bool = true, const S: bool = false, const A: bool = false> {
+    /// Inner value
+    pub inner: T,
+}
+
+impl<T> JsonStr<T> {
+    #[inline]
+    /// Create a new [`JsonStr`] with default settings (quoted, `/` and non-ASCII
+    /// characters not escaped).
+    pub const fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, const Q: bool, const S: bool, const A: bool> JsonStr<T, Q, S, A> {
+    #[inline]
+    /// Set whether to write the surrounding quotes.
+    pub fn set_quoted<const NQ: bool>(self) -> JsonStr<T, NQ, S, A> {
+        JsonStr { inner: self.inner }
+    }
+
+    #[inline]
+    /// Set whether to escape `/` as `\/`.
+    pub fn set_escape_slash<const NS: bool>(self) -> JsonStr<T, Q, NS, A> {
+        JsonStr { inner: self.inner }
+    }
+
+    #[inline]
+    /// Set whether to escape non-ASCII characters as `\uXXXX`.
+    pub fn set_ascii_only<const NA: bool>(self) -> JsonStr<T, Q, S, NA> {
+        JsonStr { inner: self.inner }
+    }
+}
+
+impl<T: StringT, const Q: bool, const S: bool, const A: bool> StringT for JsonStr<T, Q, S, A> {
+    #[inline]
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        if Q {
+            string.push(b'"');
+        }
+
+        let start = string.len();
+
+        self.inner.encode_to_buf(&mut Uncommitted(&mut *string));
+
+        escape_in_place::<B, S, A>(string, start);
+
+        if Q {
+            string.push(b'"');
+        }
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.len_hint();
+        let quotes_len = usize::from(Q) * 2;
+
+        // Each byte will be escaped to at most 6 bytes.
+        (
+            lower.saturating_add(quotes_len),
+            upper
+                .and_then(|upper| upper.checked_mul(6))
+                .and_then(|upper| upper.checked_add(quotes_len)),
+        )
+    }
+}
+
+impl<T: StringT, const Q: bool, const S: bool, const A: bool> StringExtT for JsonStr<T, Q, S, A> {}
+
+#[inline]
+/// Returns the escaped length of the byte, `0` for UTF-8 continuation bytes.
+const fn escaped_len<const S: bool, const A: bool>(byte: u8) -> usize {
+    match byte {
+        b'"' | b'\\' | b'\x08' | b'\x0C' | b'\n' | b'\r' | b'\t' => 2,
+        b'/' if S => 2,
+        0x00..=0x1F => 6,
+        0x80..=0xBF if A => 0,
+        0xC0..=0xEF if A => 6,
+        0xF0..=0xFF if A => 12,
+        _ => 1,
+    }
+}
+
+/// Escape the bytes written since `start` in place, from back to front.
+pub(crate) fn escape_in_place<B: BufT, const S: bool, const A: bool>(string: &mut B, start: usize) {
+    let end = string.len();
+
+    let Some(written) = string.as_mut_slice().get(start..end) else {
+        return;
+    };
+
+    let escaped_len: usize = written.iter().map(|&byte| escaped_len::<S, A>(byte)).sum();
+
+    let Some(extra_len) = escaped_len
+        .checked_sub(end - start)
+        .filter(|&extra_len| extra_len > 0)
+    else {
+        return;
+    };
+
+    string.resize(end + extra_len, 0);
+
+    let Some(buf) = string.as_mut_slice().get_mut(..end + extra_len) else {
+        return;
+    };
+    let mut read = end;
+    let mut write = end + extra_len;
+
+    while read < write {
+        read -= 1;
+
+        let byte = buf[read];
+
+        let escaped: &[u8] = match byte {
+            b'"' => b"\\\"",
+            b'\\' => b"\\\\",
+            b'\x08' => b"\\b",
+            b'\x0C' => b"\\f",
+            b'\n' => b"\\n",
+            b'\r' => b"\\r",
+            b'\t' => b"\\t",
+            b'/' if S => b"\\/",
+            0x00..=0x1F => &unicode_escape(byte as u16),
+            0x80..=0xFF if A => {
+                // Find the start of the char.
+                let char_end = read + 1;
+                while buf[read] & 0xC0 == 0x80 {
+                    read -= 1;
+                }
+
+                let ch = std::str::from_utf8(&buf[read..char_end])
+                    .ok()
+                    .and_then(|s| s.chars().next())
+                    .unwrap_or(char::REPLACEMENT_CHARACTER);
+
+                let mut units = [0; 2];
+                let units = ch.encode_utf16(&mut units);
+
+                write -= units.len() * 6;
+                units.iter().enumerate().for_each(|(idx, &unit)| {
+                    buf[write + idx * 6..write + idx * 6 + 6]
+                        .copy_from_slice(&unicode_escape(unit));
+                });
+
+                continue;
+            }
+            _ => {
+                write -= 1;
+                buf[write] = byte;
+
+                continue;
+            }
+        };
+
+        write -= escaped.len();
+        buf[write..write + escaped.len()].copy_from_slice(escaped);
+    }
+}
+
+#[inline]
+/// `\uXXXX`
+const fn unicode_escape(unit: u16) -> [u8; 6] {
+    [
+        b'\\',
+        b'u',
+        HEX_CHARS_LOWER[(unit >> 12) as usize],
+        HEX_CHARS_LOWER[((unit >> 8) & 0xF) as usize],
+        HEX_CHARS_LOWER[((unit >> 4) & 0xF) as usize],
+        HEX_CHARS_LOWER[(unit & 0xF) as usize],
+    ]
+}
+
+// =============================================================================
+
+/// Trait for values which can be written as a JSON value.
+///
+/// - Numbers are written as is, while non-finite floats are written as `null`.
+/// - Strings and chars are escaped and quoted, see [`JsonStr`].
+/// - `None` is written as `null`.
+/// - `Vec<T>`, `[T; N]`, `&[T]` and [`IterWrapper`] are written as arrays.
+/// - [`JsonObject`] and [`JsonArray`] are written as objects and arrays.
+pub trait JsonValueT {
+    /// Encode the value to the given buffer as a JSON value.
+    fn encode_json<B: BufT>(self, string: &mut B);
+}
+
+macro_rules! impl_json_value_t {
+    (NUMBER: $($ty:ty)*) => {
+        $(
+            impl JsonValueT for $ty {
+                #[inline]
+                fn encode_json<B: BufT>(self, string: &mut B) {
+                    self.encode_to_buf(string);
+                }
+            }
+
+            impl JsonValueT for &$ty {
+                #[inline]
+                fn encode_json<B: BufT>(self, string: &mut B) {
+                    (*self).encode_json(string);
+                }
+            }
+        )*
+    };
+    (FLOAT: $($ty:ty)*) => {
+        $(
+            impl JsonValueT for $ty {
+                #[inline]
+                fn encode_json<B: BufT>(self, string: &mut B) {
+                    if self.is_finite() {
+                        self.encode_to_buf(string);
+                    } else {
+                        string.extend_from_slice(b"null");
+                    }
+                }
+            }
+
+            impl JsonValueT for &$ty {
+                #[inline]
+                fn encode_json<B: BufT>(self, string: &mut B) {
+                    (*self).encode_json(string);
+                }
+            }
+        )*
+    };
+    (STRING: $($ty:ty),*) => {
+        $(
+            impl JsonValueT for $ty {
+                #[inline]
+                fn encode_json<B: BufT>(self, string: &mut B) {
+                    JsonStr::new(self).encode_to_buf(string);
+                }
+            }
+        )*
+    };
+}
+
+impl_json_value_t!(NUMBER: u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+impl_json_value_t!(FLOAT: f32 f64);
+impl_json_value_t!(STRING: &str, &&str, String, &String, Cow<'_, str>, &Cow<'_, str>, Arc<str>, Rc<str>, Box<str>, char, &char);
+
+impl JsonValueT for bool {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        string.extend_from_slice(if self { b"true" } else { b"false" });
+    }
+}
+
+impl JsonValueT for &bool {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        (*self).encode_json(string);
+    }
+}
+
+impl<T: JsonValueT> JsonValueT for Option<T> {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        match self {
+            Some(value) => value.encode_json(string),
+            None => string.extend_from_slice(b"null"),
+        }
+    }
+}
+
+impl<T: StringT, const S: bool, const A: bool> JsonValueT for JsonStr<T, true, S, A> {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        self.encode_to_buf(string);
+    }
+}
+
+impl<I> JsonValueT for IterWrapper<I>
+where
+    I: Iterator,
+    I::Item: JsonValueT,
+{
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        string.push(b'[');
+
+        for (idx, item) in self.inner.enumerate() {
+            if idx > 0 {
+                string.push(b',');
+            }
+
+            item.encode_json(string);
+        }
+
+        string.push(b']');
+    }
+}
+
+impl<T: JsonValueT> JsonValueT for Vec<T> {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        IterWrapper::new(self.into_iter()).encode_json(string);
+    }
+}
+
+impl<T: JsonValueT, const N: usize> JsonValueT for [T; N] {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        IterWrapper::new(self.into_iter()).encode_json(string);
+    }
+}
+
+impl<'a, T> JsonValueT for &'a [T]
+where
+    &'a T: JsonValueT,
+{
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        IterWrapper::new(self.iter()).encode_json(string);
+    }
+}
+
+impl<'a, T> JsonValueT for &'a Vec<T>
+where
+    &'a T: JsonValueT,
+{
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        self.as_slice().encode_json(string);
+    }
+}
+
+/// Fields of a [`JsonObject`], a cons list like `((k1, v1), ((k2, v2), ()))`.
+///
+/// Keys are any [`StringT`] and values are any [`JsonValueT`].
+pub trait JsonFieldsT {
+    /// Encode the fields, with a leading comma unless `first`.
+    fn encode_fields<B: BufT>(self, string: &mut B, first: bool);
+}
+
+impl JsonFieldsT for () {
+    #[inline]
+    fn encode_fields<B: BufT>(self, _string: &mut B, _first: bool) {}
+}
+
+impl<K, V, R> JsonFieldsT for ((K, V), R)
+where
+    K: StringT,
+    V: JsonValueT,
+    R: JsonFieldsT,
+{
+    #[inline]
+    fn encode_fields<B: BufT>(self, string: &mut B, first: bool) {
+        let ((key, value), rest) = self;
+
+        if !first {
+            string.push(b',');
+        }
+
+        JsonStr::new(key).encode_to_buf(string);
+        string.push(b':');
+        value.encode_json(string);
+
+        rest.encode_fields(string, false);
+    }
+}
+
+/// Elements of a [`JsonArray`], a cons list like `(v1, (v2, ()))`.
+///
+/// Elements are any [`JsonValueT`].
+pub trait JsonElementsT {
+    /// Encode the elements, with a leading comma unless `first`.
+    fn encode_elements<B: BufT>(self, string: &mut B, first: bool);
+}
+
+impl JsonElementsT for () {
+    #[inline]
+    fn encode_elements<B: BufT>(self, _string: &mut B, _first: bool) {}
+}
+
+impl<V, R> JsonElementsT for (V, R)
+where
+    V: JsonValueT,
+    R: JsonElementsT,
+{
+    #[inline]
+    fn encode_elements<B: BufT>(self, string: &mut B, first: bool) {
+        let (value, rest) = self;
+
+        if !first {
+            string.push(b',');
+        }
+
+        value.encode_json(string);
+
+        rest.encode_elements(string, false);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// JSON object, see [`json_obj!`](crate::json_obj).
+///
+/// Every field is written exactly once, so commas are written only between
+/// fields and never need to be removed afterwards.
+pub struct JsonObject<F> {
+    fields: F,
+}
+
+impl<F: JsonFieldsT> JsonObject<F> {
+    #[inline]
+    /// Create a new [`JsonObject`] from the fields, see [`JsonFieldsT`].
+    ///
+    /// Prefer [`json_obj!`](crate::json_obj).
+    pub const fn new(fields: F) -> Self {
+        Self { fields }
+    }
+}
+
+impl<F: JsonFieldsT> JsonValueT for JsonObject<F> {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        string.push(b'{');
+        self.fields.encode_fields(string, true);
+        string.push(b'}');
+    }
+}
+
+impl<F: JsonFieldsT> StringT for JsonObject<F> {
+    #[inline]
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        self.encode_json(string);
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (2, None)
+    }
+}
+
+impl<F: JsonFieldsT> StringExtT for JsonObject<F> {}
+
+#[derive(Debug, Clone, Copy)]
+/// JSON array with elements of any type, see [`json_arr!`](crate::json_arr).
+///
+/// For elements of the same type, `Vec<T>` or `[T; N]` can be used directly.
+pub struct JsonArray<E> {
+    elements: E,
+}
+
+impl<E: JsonElementsT> JsonArray<E> {
+    #[inline]
+    /// Create a new [`JsonArray`] from the elements, see [`JsonElementsT`].
+    ///
+    /// Prefer [`json_arr!`](crate::json_arr).
+    pub const fn new(elements: E) -> Self {
+        Self { elements }
+    }
+}
+
+impl<E: JsonElementsT> JsonValueT for JsonArray<E> {
+    #[inline]
+    fn encode_json<B: BufT>(self, string: &mut B) {
+        string.push(b'[');
+        self.elements.encode_elements(string, true);
+        string.push(b']');
+    }
+}
+
+impl<E: JsonElementsT> StringT for JsonArray<E> {
+    #[inline]
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        self.encode_json(string);
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (2, None)
+    }
+}
+
+impl<E: JsonElementsT> StringExtT for JsonArray<E> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{str_concat, string::PushAnyT};
+
+    #[test]
+    fn test_json_str() {
+        assert_eq!(JsonStr::new("").to_string_ext(), r#""""#);
+        assert_eq!(JsonStr::new("hello").to_string_ext(), r#""hello""#);
+        assert_eq!(
+            JsonStr::new("\"\\/\x08\x0C\n\r\t\x00\x1F\x7F").to_string_ext(),
+            "\"\\\"\\\\/\\b\\f\\n\\r\\t\\u0000\\u001f\x7F\""
+        );
+        assert_eq!(
+            JsonStr::new("a/b")
+                .set_escape_slash::<true>()
+                .to_string_ext(),
+            r#""a\/b""#
+        );
+        assert_eq!(
+            JsonStr::new("a\"b").set_quoted::<false>().to_string_ext(),
+            r#"a\"b"#
+        );
+    }
+
+    #[test]
+    fn test_json_str_ascii_only() {
+        let data = "é你😀\n";
+
+        assert_eq!(JsonStr::new(data).to_string_ext(), "\"é你😀\\n\"");
+        assert_eq!(
+            JsonStr::new(data).set_ascii_only::<true>().to_string_ext(),
+            r#""\u00e9\u4f60\ud83d\ude00\n""#
+        );
+    }
+
+    #[test]
+    fn test_json_str_nested() {
+        assert_eq!(
+            str_concat!(
+                "{\"name\":",
+                JsonStr::new(("a\"", 1u8, Some('\t'))),
+                ",\"tags\":[",
+                JsonStr::new("x").set_quoted::<false>(),
+                "]}"
+            ),
+            r#"{"name":"a\"1\t","tags":[x]}"#
+        );
+
+        // Existing content is untouched.
+        let mut string = "\"".to_string();
+        string.push_any(JsonStr::new("\"").set_ascii_only::<true>());
+        assert_eq!(string, r#"""\"""#);
+    }
+
+    #[test]
+    fn test_json_obj() {
+        assert_eq!(json_obj! {}.to_string_ext(), "{}");
+        assert_eq!(json_arr![].to_string_ext(), "[]");
+
+        let name = String::from("a\"b");
+        let key = "dyn\nkey";
+        let tags = vec!["x", "y"];
+
+        assert_eq!(
+            json_obj! {
+                "id": 1u64,
+                "neg": -2i32,
+                "name": &name,
+                key: 'c',
+                ("com"): true,
+                "tags": &tags,
+                "empty": Vec::<u8>::new(),
+                "none": None::<&str>,
+                "some": Some(1.5f64),
+                "nan": f64::NAN,
+            }
+            .to_string_ext(),
+            r#"{"id":1,"neg":-2,"name":"a\"b","dyn\nkey":"c","com":true,"tags":["x","y"],"empty":[],"none":null,"some":1.5,"nan":null}"#
+        );
+    }
+
+    #[test]
+    fn test_json_nested() {
+        let rows = [(1u8, "a"), (2u8, "b")];
+
+        let data = json_obj! {
+            "rows": IterWrapper::new(rows.iter().map(|(id, name)| json_obj! { "id": id, "name": *name })),
+            "matrix": [[1u8, 2], [3, 4]],
+            "mixed": json_arr![json_obj! { "k": json_arr![] }, None::<u8>, JsonStr::new(("user-", 1u8))],
+        };
+
+        assert_eq!(
+            data.to_string_ext(),
+            r#"{"rows":[{"id":1,"name":"a"},{"id":2,"name":"b"}],"matrix":[[1,2],[3,4]],"mixed":[{"k":[]},null,"user-1"]}"#
+        );
+
+        // Existing content is untouched, composes with other `StringT`.
+        let mut string = "data=".to_string();
+        string.push_any(json_arr![1u8, "/"]);
+        assert_eq!(string, r#"data=[1,"/"]"#);
+        assert_eq!(
+            str_concat!(sep = "\n"; json_obj! { "a": 1u8 }, json_obj! {}),
+            "{\"a\":1}\n{}"
+        );
+    }
+
+    #[test]
+    fn test_json_str_write_to_fmt() {
+        let value = || JsonStr::new(IterWrapper::new((0..2000u32).map(|n| (n, "\"\n"))));
+        let mut string = String::new();
+        value().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, value().to_string_ext());
+        assert!(!string.contains('\n'));
+    }
+}
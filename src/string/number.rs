@@ -1,55 +1,209 @@
 //! Number to string, fast and efficient utilities.
 
-use std::ops;
+use std::{fmt, marker::PhantomData, ops, str::FromStr};
 
-use super::{StringExtT, StringT};
+mod float;
+
+pub use self::float::{FloatStr, Notation, Precision};
+use super::{BufT, Separator, StringExtT, StringT};
 use crate::impl_for_shared_ref;
 
-/// Hexadecimal characters in lower case.
-static HEX_CHARS_LOWER: [u8; 16] = [
-    b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'a', b'b', b'c', b'd', b'e', b'f',
-];
+/// Digits for bases up to 36, in lower case.
+static DIGITS_LOWER: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Digits for bases up to 36, in upper case.
+static DIGITS_UPPER: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Digits for base 62.
+static DIGITS_BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// `00`, `01`, ..., `99`, for decimal formatting two digits at a time.
+static DEC_DIGITS_LUT: &[u8; 200] = &{
+    let mut lut = [0; 200];
+    let mut idx = 0;
+    while idx < 100 {
+        lut[idx * 2] = b'0' + (idx / 10) as u8;
+        lut[idx * 2 + 1] = b'0' + (idx % 10) as u8;
+        idx += 1;
+    }
+    lut
+};
+
+#[inline]
+/// Write the decimal digits of `num` at the end of `buf`, two digits at a time
+/// (like `itoa`). The remaining leading part of `buf` is untouched.
+fn write_decimal_u64(mut num: u64, buf: &mut [u8]) {
+    let mut pos = buf.len();
+
+    while num >= 10000 {
+        let rem = (num % 10000) as usize;
+        num /= 10000;
+
+        let (high, low) = ((rem / 100) * 2, (rem % 100) * 2);
+        buf[pos - 4..pos - 2].copy_from_slice(&DEC_DIGITS_LUT[high..high + 2]);
+        buf[pos - 2..pos].copy_from_slice(&DEC_DIGITS_LUT[low..low + 2]);
+        pos -= 4;
+    }
+
+    // `num` < 10000 now.
+    let mut num = num as usize;
+
+    if num >= 100 {
+        let low = (num % 100) * 2;
+        num /= 100;
+
+        buf[pos - 2..pos].copy_from_slice(&DEC_DIGITS_LUT[low..low + 2]);
+        pos -= 2;
+    }
+
+    if num >= 10 {
+        buf[pos - 2..pos].copy_from_slice(&DEC_DIGITS_LUT[num * 2..num * 2 + 2]);
+    } else {
+        buf[pos - 1] = b'0' + num as u8;
+    }
+}
+
+#[inline]
+/// Like [`write_decimal_u64`], `buf` should be pre-filled with `0`.
+fn write_decimal_u128(mut num: u128, buf: &mut [u8]) {
+    let mut end = buf.len();
+
+    while num > u128::from(u64::MAX) {
+        let (quotient, rem) = udivmod_1e19(num);
+        write_decimal_u64(rem, &mut buf[end - 19..end]);
+        num = quotient;
+        end -= 19;
+    }
+
+    write_decimal_u64(num as u64, &mut buf[..end]);
+}
+
+#[inline]
+/// `num.ilog10()` for `num` > `u64::MAX`, which does `u128` divisions in std.
+const fn ilog10_u128(num: u128) -> u32 {
+    match udivmod_1e19(num).0 {
+        // u128::MAX < 10^39
+        quotient if quotient > u64::MAX as u128 => 38,
+        quotient => 19 + (quotient as u64).ilog10(),
+    }
+}
+
+#[inline]
+/// Divide `num` by `10^19` (the max power of 10 within `u64`), returns the
+/// quotient and the remainder.
+///
+/// `u128` division is a slow library call, multiply by the magic number
+/// `ceil(2^190 / 10^19)` instead, see "Division by Invariant Integers using
+/// Multiplication" by Granlund and Montgomery.
+const fn udivmod_1e19(num: u128) -> (u128, u64) {
+    const DIV: u64 = 10_000_000_000_000_000_000;
+
+    let quotient = if num < 1 << 83 {
+        ((num >> 19) as u64 / (DIV >> 19)) as u128
+    } else {
+        u128_mulhi(num, 156_927_543_384_667_019_095_894_735_580_191_660_403) >> 62
+    };
+
+    (quotient, (num - quotient * DIV as u128) as u64)
+}
+
+#[inline]
+/// The upper 128 bits of the 256 bits product.
+const fn u128_mulhi(x: u128, y: u128) -> u128 {
+    let (x_lo, x_hi) = (x as u64 as u128, x >> 64);
+    let (y_lo, y_hi) = (y as u64 as u128, y >> 64);
+
+    let carry = (x_lo * y_lo) >> 64;
+    let m = x_lo * y_hi + carry;
+    let high1 = m >> 64;
+    let high2 = (x_hi * y_lo + (m as u64 as u128)) >> 64;
 
-/// Hexadecimal characters in upper case.
-static HEX_CHARS_UPPER: [u8; 16] = [
-    b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'A', b'B', b'C', b'D', b'E', b'F',
-];
+    x_hi * y_hi + high1 + high2
+}
 
 // === impls ===
 
+/// Trait for the sign of [`NumStr`], negative numbers always have `-`.
+pub trait SignT {
+    /// The sign written before non-negative numbers.
+    const POSITIVE: &'static [u8];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Default)]
+/// Only negative numbers have the sign `-`, like `42` and `-42`.
+pub struct SignMinus;
+
+impl SignT for SignMinus {
+    const POSITIVE: &'static [u8] = b"";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Default)]
+/// Always write the sign, like `+42` and `-42`.
+pub struct SignPlus;
+
+impl SignT for SignPlus {
+    const POSITIVE: &'static [u8] = b"+";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Default)]
+/// Write a space for non-negative numbers, like ` 42` and `-42`, so that
+/// positive and negative numbers are aligned.
+pub struct SignSpace;
+
+impl SignT for SignSpace {
+    const POSITIVE: &'static [u8] = b" ";
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
 #[repr(transparent)]
 /// Number to string
 ///
 /// # Generic
 ///
-/// - `B`: the base of the number, should be within the range `2..=16`. Default
-///   is 10.
-/// - `U`: whether to use uppercase for hex. Default is lowercase (false).
+/// - `B`: the base of the number, should be within the range `2..=36`, or 62.
+///   Default is 10.
+///
+///   For base 62, digits are `0-9A-Za-z`.
+/// - `U`: whether to use uppercase for bases > 10 (except for base 62). Default
+///   is lowercase (false).
 ///
 ///   For float number, `U` means whether only to reserve the integer part.
 /// - `R`: the resize length of the string. The overflow part will be truncated,
 ///   and the insufficient part will be filled with '0'. Default is 0, or no
 ///   resize.
 /// - `M`: the minimum length of the string, if the length of the string is less
-///   than `M`, fill with '0'.  Default is 0, or no minimum.  For signed number
-///   `M` will be ignored.
+///   than `M`, fill with '0'.  Default is 0, or no minimum.
+///
+///   For integers, `R` and `M` count the digits only, the zeros are filled
+///   between the sign and the digits, like `-0042`.
 ///
+///   For float numbers, `R` and `M` count the digits after the decimal point
+///   of the shortest representation instead, which is always in positional
+///   notation like `0.0000001`. `R` **truncates** the fraction, like `1.99`
+///   to `1.9` with `R` = 1. Use [`with_precision`](NumStr::with_precision)
+///   for rounding.
 /// - `T`: the underlying type of the number. Default is `usize`.
+/// - `S`: the sign of the number, see [`SignT`]. Default is [`SignMinus`].
 ///
-/// # Panic
+/// # Compile error
 ///
-/// - Invalid base (== 0 or > 16)
+/// Invalid base is rejected at compile time.
+///
+/// ```rust,compile_fail
+/// # use macro_toolset::string::{NumStr, StringExtT};
+/// NumStr::new_default(1u8).set_custom_base::<37>().to_string_ext();
+/// ```
 pub struct NumStr<
     const B: u8 = 10,
     const U: bool = false,
     const R: usize = 0,
     const M: usize = 0,
     T = usize,
->(T);
+    S = SignMinus,
+>(T, PhantomData<S>);
 
-impl<const B: u8, const U: bool, const R: usize, const M: usize, T> AsRef<T>
-    for NumStr<B, U, R, M, T>
+impl<const B: u8, const U: bool, const R: usize, const M: usize, T, S> AsRef<T>
+    for NumStr<B, U, R, M, T, S>
 {
     #[inline]
     fn as_ref(&self) -> &T {
@@ -57,8 +211,8 @@ impl<const B: u8, const U: bool, const R: usize, const M: usize, T> AsRef<T>
     }
 }
 
-impl<const B: u8, const U: bool, const R: usize, const M: usize, T> ops::Deref
-    for NumStr<B, U, R, M, T>
+impl<const B: u8, const U: bool, const R: usize, const M: usize, T, S> ops::Deref
+    for NumStr<B, U, R, M, T, S>
 {
     type Target = T;
 
@@ -67,8 +221,8 @@ impl<const B: u8, const U: bool, const R: usize, const M: usize, T> ops::Deref
     }
 }
 
-impl<const B: u8, const U: bool, const R: usize, const M: usize, T> ops::DerefMut
-    for NumStr<B, U, R, M, T>
+impl<const B: u8, const U: bool, const R: usize, const M: usize, T, S> ops::DerefMut
+    for NumStr<B, U, R, M, T, S>
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
@@ -92,7 +246,7 @@ impl<T> NumStr<10, false, 0, 0, T> {
     ///
     /// ## Notice
     ///
-    /// For negative number, `R`, `M` will not make sense
+    /// For signed number, `R`, `M` count the digits only, like `-0042`.
     ///
     /// ## Examples
     ///
@@ -103,7 +257,7 @@ impl<T> NumStr<10, false, 0, 0, T> {
     /// # ;
     /// ```
     pub fn new_default(inner: T) -> Self {
-        NumStr(inner)
+        NumStr(inner, PhantomData)
     }
 
     #[inline]
@@ -120,7 +274,7 @@ impl<T> NumStr<10, false, 0, 0, T> {
     ///
     /// ## Notice
     ///
-    /// For negative number, `R`, `M` will not make sense
+    /// For signed number, `R`, `M` count the digits only, like `-0042`.
     ///
     /// ## Examples
     ///
@@ -131,7 +285,7 @@ impl<T> NumStr<10, false, 0, 0, T> {
     /// # ;
     /// ```
     pub fn hex_default(inner: T) -> NumStr<16, false, 0, 0, T> {
-        NumStr(inner)
+        NumStr(inner, PhantomData)
     }
 }
 
@@ -148,10 +302,6 @@ impl NumStr<10, false, 0, 0, u8> {
     ///
     /// See [`NumStr`] for details.
     ///
-    /// ## Notice
-    ///
-    /// For negative number, `R`, `M` will not make sense
-    ///
     /// ## Examples
     ///
     /// ```rust
@@ -163,37 +313,52 @@ impl NumStr<10, false, 0, 0, u8> {
     /// assert_eq!(nums.to_string_ext(), "11451419198100");
     /// ```
     pub fn hex_byte_default(inner: u8) -> NumStr<16, false, 2, 0, u8> {
-        NumStr(inner)
+        NumStr(inner, PhantomData)
     }
 }
 
-impl<const B: u8, const U: bool, const R: usize, const M: usize, T> NumStr<B, U, R, M, T> {
+impl<const B: u8, const U: bool, const R: usize, const M: usize, T, S> NumStr<B, U, R, M, T, S> {
     #[inline]
     /// Create a new [`NumStr`] with the given number.
     pub fn new(inner: T) -> Self {
-        NumStr(inner)
+        NumStr(inner, PhantomData)
     }
 
     #[inline]
     /// Convert to decimal representation.
-    pub fn decimal(self) -> NumStr<10, U, R, M, T> {
-        NumStr(self.0)
+    pub fn decimal(self) -> NumStr<10, U, R, M, T, S> {
+        NumStr(self.0, PhantomData)
     }
 
     #[inline]
     /// Convert to hexadecimal representation.
-    pub fn hexadecimal(self) -> NumStr<16, U, R, M, T> {
-        NumStr(self.0)
+    pub fn hexadecimal(self) -> NumStr<16, U, R, M, T, S> {
+        NumStr(self.0, PhantomData)
     }
 
+    /// Compile time check of the base.
+    const BASE_CHECK: () = assert!(
+        matches!(B, 2..=36 | 62),
+        "unsupported base, should be within the range `2..=36`, or 62"
+    );
+
     #[inline]
     /// Set custom base.
     ///
-    /// The valid range is `2..=16`
-    pub fn set_custom_base<const NB: u8>(self) -> NumStr<NB, U, R, M, T> {
-        debug_assert!(NB >= 2 && NB <= 16);
+    /// The valid range is `2..=36`, or 62, checked at compile time.
+    ///
+    /// ```rust
+    /// # use macro_toolset::string::{NumStr, StringExtT};
+    /// let num = NumStr::new_default(123456789_u64);
+    /// assert_eq!(num.set_custom_base::<36>().to_string_ext(), "21i3v9");
+    /// assert_eq!(num.set_custom_base::<36>().set_uppercase::<true>().to_string_ext(), "21I3V9");
+    /// assert_eq!(num.set_custom_base::<62>().to_string_ext(), "8M0kX");
+    /// ```
+    pub fn set_custom_base<const NB: u8>(self) -> NumStr<NB, U, R, M, T, S> {
+        #[allow(clippy::let_unit_value)]
+        let () = NumStr::<NB, U, R, M, T, S>::BASE_CHECK;
 
-        NumStr(self.0)
+        NumStr(self.0, PhantomData)
     }
 
     #[inline]
@@ -202,8 +367,8 @@ impl<const B: u8, const U: bool, const R: usize, const M: usize, T> NumStr<B, U,
     /// Default is lowercase
     ///
     /// Note: only works for base > 10
-    pub fn set_uppercase<const NU: bool>(self) -> NumStr<B, NU, R, M, T> {
-        NumStr(self.0)
+    pub fn set_uppercase<const NU: bool>(self) -> NumStr<B, NU, R, M, T, S> {
+        NumStr(self.0, PhantomData)
     }
 
     #[inline]
@@ -215,8 +380,8 @@ impl<const B: u8, const U: bool, const R: usize, const M: usize, T> NumStr<B, U,
     /// Default is not resize
     ///
     /// Note: see [`Vec::resize`] for details
-    pub fn set_resize_len<const NR: usize>(self) -> NumStr<B, U, NR, M, T> {
-        NumStr(self.0)
+    pub fn set_resize_len<const NR: usize>(self) -> NumStr<B, U, NR, M, T, S> {
+        NumStr(self.0, PhantomData)
     }
 
     #[inline]
@@ -227,107 +392,269 @@ impl<const B: u8, const U: bool, const R: usize, const M: usize, T> NumStr<B, U,
     /// Default is not minimum
     ///
     /// Note: if set `Self::should_resize`, the minimum length will be ignored
-    pub fn set_minimum_len<const NM: usize>(self) -> NumStr<B, U, R, NM, T> {
-        NumStr(self.0)
+    pub fn set_minimum_len<const NM: usize>(self) -> NumStr<B, U, R, NM, T, S> {
+        NumStr(self.0, PhantomData)
+    }
+
+    #[inline]
+    /// Set the sign, see [`SignT`].
+    ///
+    /// ```rust
+    /// # use macro_toolset::string::{number::{SignPlus, SignSpace}, NumStr, StringExtT};
+    /// let num = NumStr::new_default(42_i32).set_minimum_len::<4>();
+    /// assert_eq!(num.to_string_ext(), "0042");
+    /// assert_eq!(num.set_sign::<SignPlus>().to_string_ext(), "+0042");
+    /// assert_eq!(num.set_sign::<SignSpace>().to_string_ext(), " 0042");
+    /// assert_eq!(NumStr::new_default(-42_i32).set_minimum_len::<4>().to_string_ext(), "-0042");
+    /// ```
+    pub fn set_sign<NS>(self) -> NumStr<B, U, R, M, T, NS> {
+        NumStr(self.0, PhantomData)
+    }
+
+    #[inline]
+    /// Group the digits of the integer part with the given [`Grouping`].
+    ///
+    /// `R` and `M` count the digits only, the zero-padded digits are grouped
+    /// too.
+    ///
+    /// ```rust
+    /// # use macro_toolset::string::{number::Grouping, NumStr, StringExtT};
+    /// let num = NumStr::new_default(1234567_u32);
+    /// assert_eq!(num.grouped(Grouping::THOUSANDS).to_string_ext(), "1,234,567");
+    /// assert_eq!(num.grouped(Grouping::INDIAN).to_string_ext(), "12,34,567");
+    /// assert_eq!(
+    ///     num.set_minimum_len::<8>().grouped(Grouping::new(4, '_')).to_string_ext(),
+    ///     "0123_4567"
+    /// );
+    /// ```
+    pub const fn grouped(self, grouping: Grouping) -> Grouped<B, U, R, M, T, S> {
+        Grouped {
+            inner: self,
+            grouping,
+        }
     }
 
     #[inline]
     fn charset() -> &'static [u8] {
-        debug_assert!(B >= 2 && B <= 16, "unsupported base: {}", B);
+        #[allow(clippy::let_unit_value)]
+        let () = Self::BASE_CHECK;
 
-        if U {
-            &HEX_CHARS_UPPER
-        } else {
-            &HEX_CHARS_LOWER
+        match B {
+            62 => DIGITS_BASE62,
+            _ if U => DIGITS_UPPER,
+            _ => DIGITS_LOWER,
         }
     }
 }
 
-impl<const B: u8, const U: bool, const R: usize, const M: usize> NumStr<B, U, R, M, f32> {
+impl<const B: u8, const U: bool, const R: usize, const M: usize, S> NumStr<B, U, R, M, f32, S> {
     #[inline]
     /// Set integer only mode.
     ///
     /// Default disable.
-    pub fn set_integer_only<const NU: bool>(self) -> NumStr<B, NU, R, M, f32> {
-        NumStr(self.0)
+    pub fn set_integer_only<const NU: bool>(self) -> NumStr<B, NU, R, M, f32, S> {
+        NumStr(self.0, PhantomData)
     }
 }
 
-impl<const B: u8, const U: bool, const R: usize, const M: usize> NumStr<B, U, R, M, f64> {
+impl<const B: u8, const U: bool, const R: usize, const M: usize, S> NumStr<B, U, R, M, f64, S> {
     #[inline]
     /// Set integer only mode.
     ///
     /// Default disable.
-    pub fn set_integer_only<const NU: bool>(self) -> NumStr<B, NU, R, M, f64> {
-        NumStr(self.0)
+    pub fn set_integer_only<const NU: bool>(self) -> NumStr<B, NU, R, M, f64, S> {
+        NumStr(self.0, PhantomData)
     }
 }
 
-macro_rules! impl_num_str {
-    (UNSIGNED: $($ty:ty) +) => {
-        $(
-            impl<const B: u8, const U: bool, const R: usize, const M: usize> NumStr<B, U, R, M, $ty> {
-                #[inline]
-                /// Encode the number to the str
-                pub fn encode(self, string: &mut Vec<u8>) {
-                    let current_ptr = string.len();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Digit grouping of [`NumStr`], see [`NumStr::grouped`].
+pub struct Grouping {
+    first: usize,
+    size: usize,
+    separator: char,
+}
 
-                    if R > 0 {
-                        string.resize(current_ptr + R, b'0');
+impl Grouping {
+    /// `1,234,567`
+    pub const THOUSANDS: Self = Self::new(3, ',');
 
-                        let (mut num, charset) = if self.0 == 0 {
-                            return
-                        } else {
-                            (self.0, Self::charset())
-                        };
+    /// `1_234_567`, like Rust integer literals.
+    pub const UNDERSCORE: Self = Self::new(3, '_');
 
-                        let string = &mut string[current_ptr..current_ptr + R];
+    /// `12,34,567`, the Indian numbering system.
+    pub const INDIAN: Self = Self::new(2, ',').first_size(3);
 
-                        let mut count = 0;
+    #[inline]
+    /// Create a new [`Grouping`] with given group size and separator.
+    ///
+    /// Size 0 means no grouping.
+    pub const fn new(size: usize, separator: char) -> Self {
+        Self {
+            first: size,
+            size,
+            separator,
+        }
+    }
 
-                        while let Some(s) = string.get_mut(count) {
-                            *s = charset[(num % B as $ty) as usize];
-                            num /= B as $ty;
-                            count += 1;
+    #[inline]
+    /// Set the size of the first (rightmost) group, which defaults to the group
+    /// size.
+    pub const fn first_size(mut self, first: usize) -> Self {
+        self.first = first;
+        self
+    }
 
-                            if num <= 0 {
-                                break
-                            }
-                        }
+    #[inline]
+    /// Returns how many separators to insert between `digits` digits.
+    const fn separators(&self, digits: usize) -> usize {
+        if self.first == 0 || digits <= self.first {
+            return 0;
+        }
 
-                        string
-                    } else {
-                        let (mut num, charset) = if self.0 == 0 {
-                            string.push(b'0');
-                            return
-                        } else {
-                            (self.0, Self::charset())
-                        };
+        match (digits - self.first - 1).checked_div(self.size) {
+            Some(rest) => 1 + rest,
+            None => 1,
+        }
+    }
 
-                        let mut count = 0;
+    /// Insert the separators into the integer part written since `start` in
+    /// place, from back to front.
+    ///
+    /// The integer part is the leading digits (after the sign, if any).
+    fn group_in_place<BUF: BufT>(&self, string: &mut BUF, start: usize, is_digit: fn(&u8) -> bool) {
+        let end = string.len();
+
+        let Some(written) = string.as_mut_slice().get(start..end) else {
+            return;
+        };
+
+        let digits_start = written
+            .iter()
+            .position(|b| !matches!(b, b'-' | b'+' | b' '))
+            .map_or(end, |pos| start + pos);
+        let digits_end = written[digits_start - start..]
+            .iter()
+            .position(|b| !is_digit(b))
+            .map_or(end, |pos| digits_start + pos);
+
+        let separators = self.separators(digits_end - digits_start);
+
+        if separators == 0 {
+            return;
+        }
 
-                        while num > 0 {
-                            count += 1;
-                            string.push(charset[(num % B as $ty) as usize]);
-                            num /= B as $ty;
-                        }
+        let mut separator = [0; 4];
+        let separator = self.separator.encode_utf8(&mut separator).as_bytes();
+        let extra_len = separators * separator.len();
+
+        string.resize(end + extra_len, 0);
+
+        let Some(buf) = string.as_mut_slice().get_mut(..end + extra_len) else {
+            return;
+        };
+        buf.copy_within(digits_end..end, digits_end + extra_len);
+
+        let mut read = digits_end;
+        let mut write = digits_end + extra_len;
+        let mut group_len = self.first;
+        let mut count = 0;
+
+        while read < write {
+            if count == group_len {
+                write -= separator.len();
+                buf[write..write + separator.len()].copy_from_slice(separator);
+
+                group_len = if self.size == 0 {
+                    usize::MAX
+                } else {
+                    self.size
+                };
+                count = 0;
+            }
 
-                        // Minimal length
-                        while count < M {
-                            count += 1;
-                            string.push(b'0');
+            read -= 1;
+            write -= 1;
+            buf[write] = buf[read];
+            count += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// [`NumStr`] with the digits grouped, see [`NumStr::grouped`].
+pub struct Grouped<
+    const B: u8 = 10,
+    const U: bool = false,
+    const R: usize = 0,
+    const M: usize = 0,
+    T = usize,
+    S = SignMinus,
+> {
+    inner: NumStr<B, U, R, M, T, S>,
+    grouping: Grouping,
+}
+
+macro_rules! impl_num_str {
+    (UNSIGNED: $($ty:ty) +) => {
+        $(
+            impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> NumStr<B, U, R, M, $ty, S> {
+                #[inline]
+                /// The exact length of the encoded string.
+                fn encoded_len(&self) -> usize {
+                    #[allow(clippy::let_unit_value)]
+                    let () = Self::BASE_CHECK;
+
+                    if R > 0 {
+                        return R;
+                    }
+
+                    #[allow(clippy::unnecessary_cast)]
+                    let digits = if B == 10 {
+                        match u64::try_from(self.0) {
+                            Ok(num) => num.checked_ilog10(),
+                            Err(_) => Some(ilog10_u128(self.0 as u128)),
                         }
+                    } else {
+                        self.0.checked_ilog(B as $ty)
+                    }
+                    .map_or(1, |log| log as usize + 1);
 
-                        let final_ptr = string.len();
-                        &mut string[current_ptr..final_ptr]
-                    }.reverse();
+                    digits.max(M)
+                }
+
+                #[inline]
+                fn encoded_len_hint(&self) -> (usize, Option<usize>) {
+                    let len = S::POSITIVE.len() + self.encoded_len();
+
+                    (len, Some(len))
                 }
 
                 #[inline]
                 /// Encode the number to the str
-                pub fn encode_bytes(self, string: &mut bytes::BytesMut) {
+                pub fn encode<BUF: BufT>(self, string: &mut BUF) {
+                    string.extend_from_slice(S::POSITIVE);
+
                     let current_ptr = string.len();
 
+                    if B == 10 && R == 0 {
+                        // Write digits directly into the space filled with `0`.
+                        let len = self.encoded_len();
+                        string.resize(current_ptr + len, b'0');
+
+                        let Some(buf) = string.as_mut_slice().get_mut(current_ptr..current_ptr + len) else {
+                            return;
+                        };
+
+                        #[allow(clippy::unnecessary_cast)]
+                        match u64::try_from(self.0) {
+                            Ok(num) => write_decimal_u64(num, buf),
+                            Err(_) => write_decimal_u128(self.0 as u128, buf),
+                        }
+
+                        return;
+                    }
+
                     if R > 0 {
                         string.resize(current_ptr + R, b'0');
 
@@ -337,7 +664,9 @@ macro_rules! impl_num_str {
                             (self.0, Self::charset())
                         };
 
-                        let string = &mut string[current_ptr..current_ptr + R];
+                        let Some(string) = string.as_mut_slice().get_mut(current_ptr..current_ptr + R) else {
+                            return;
+                        };
 
                         let mut count = 0;
 
@@ -354,7 +683,7 @@ macro_rules! impl_num_str {
                         string
                     } else {
                         let (mut num, charset) = if self.0 == 0 {
-                            string.extend(b"0");
+                            string.push(b'0');
                             return
                         } else {
                             (self.0, Self::charset())
@@ -364,75 +693,124 @@ macro_rules! impl_num_str {
 
                         while num > 0 {
                             count += 1;
-                            string.extend([charset[(num % B as $ty) as usize]]);
+                            string.push(charset[(num % B as $ty) as usize]);
                             num /= B as $ty;
                         }
 
                         // Minimal length
                         while count < M {
                             count += 1;
-                            string.extend([b'0']);
+                            string.push(b'0');
                         }
 
-                        let final_ptr = string.len();
-                        &mut string[current_ptr..final_ptr]
+                        let Some(string) = string.as_mut_slice().get_mut(current_ptr..) else {
+                            return;
+                        };
+
+                        string
                     }.reverse();
                 }
+
+                #[inline]
+                #[deprecated(since = "0.8.3", note = "Use `encode` instead")]
+                /// Encode the number to the str
+                pub fn encode_bytes(self, string: &mut bytes::BytesMut) {
+                    self.encode(string);
+                }
             }
 
-            impl_num_str!(@INTERNAL $ty);
+            impl_num_str!(@INTERNAL $ty, u8::is_ascii_alphanumeric);
         )+
     };
     (SIGNED: $($ty:ty as $uty:ty);+) => {
         $(
-            impl<const B: u8, const U: bool, const R: usize, const M: usize> NumStr<B, U, R, M, $ty> {
+            impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> NumStr<B, U, R, M, $ty, S> {
+                #[inline]
+                fn encoded_len_hint(&self) -> (usize, Option<usize>) {
+                    let sign_len = if self.is_negative() { 1 } else { S::POSITIVE.len() };
+                    let len = sign_len
+                        + NumStr::<B, U, R, M, _>::new(self.0.unsigned_abs()).encoded_len();
+
+                    (len, Some(len))
+                }
+
                 #[inline]
                 /// Encode the number to the str
-                pub fn encode(self, string: &mut Vec<u8>) {
+                ///
+                /// The sign is written first, then the zero-padded digits.
+                pub fn encode<BUF: BufT>(self, string: &mut BUF) {
                     if self.is_negative() {
                         string.push(b'-');
-                        // No resize or minimum length for signed numbers!
+                    } else {
+                        string.extend_from_slice(S::POSITIVE);
                     }
 
-                    NumStr::<B, U, 0, 0, _>::new(self.0.unsigned_abs()).encode(string);
+                    NumStr::<B, U, R, M, _>::new(self.0.unsigned_abs()).encode(string);
                 }
 
                 #[inline]
+                #[deprecated(since = "0.8.3", note = "Use `encode` instead")]
                 /// Encode the number to the str
                 pub fn encode_bytes(self, string: &mut bytes::BytesMut) {
-                    if self.is_negative() {
-                        string.extend(b"-");
-                        // No resize or minimum length for signed numbers!
-                    }
-
-                    NumStr::<B, U, 0, 0, _>::new(self.0.unsigned_abs()).encode_bytes(string);
+                    self.encode(string);
                 }
             }
 
-            impl_num_str!(@INTERNAL $ty);
+            impl_num_str!(@INTERNAL $ty, u8::is_ascii_alphanumeric);
         )+
     };
     (FLOAT: $($ty:ty) +) => {
         $(
-            impl<const B: u8, const U: bool, const R: usize, const M: usize> NumStr<B, U, R, M, $ty> {
+            impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> NumStr<B, U, R, M, $ty, S> {
+                #[inline]
+                fn encoded_len_hint(&self) -> (usize, Option<usize>) {
+                    // The shortest one is like `0` (integer only).
+                    (1, Some(float::MAX_SHORTEST_LEN + R.max(M)))
+                }
+
                 #[inline]
                 /// Encode the number to the str
-                pub fn encode(mut self, string: &mut Vec<u8>) {
+                pub fn encode<BUF: BufT>(mut self, string: &mut BUF) {
                     if U {
                         self.0 = self.0.trunc();
                     }
 
+                    // Like `format!`, `NaN` has no sign, while `-0.0` has.
+                    if self.0.is_nan() {
+                        string.extend_from_slice(b"NaN");
+                        return;
+                    }
+
+                    if self.0.is_sign_negative() {
+                        string.push(b'-');
+                    } else {
+                        string.extend_from_slice(S::POSITIVE);
+                    }
+
+                    if self.0.is_infinite() {
+                        string.extend_from_slice(b"inf");
+                        return;
+                    }
+
+                    // Without the sign.
                     let original_len = string.len();
 
-                    #[cfg(not(feature = "feat-string-ext-ryu"))]
-                    string.extend(format!("{}", self.0).as_bytes());
+                    // Always in positional notation, like `format!`. Use
+                    // `with_precision` for rounding instead of truncating.
+                    float::Shortest::from(self.0.abs()).encode(string);
+
+                    let Some(dot_pos) = string
+                        .as_mut_slice()
+                        .get(original_len..)
+                        .map(|written| written.iter().position(|&b| b == b'.'))
+                    else {
+                        return;
+                    };
 
-                    #[cfg(feature = "feat-string-ext-ryu")]
-                    string.extend(ryu::Buffer::new().format(self.0).as_bytes());
+                    match dot_pos {
+                        Some(dot_pos) => {
+                            let dot_pos = original_len + dot_pos;
 
-                    #[allow(unsafe_code)]
-                    match unsafe { std::str::from_utf8_unchecked(string) }.rfind('.') {
-                        Some(dot_pos) if self.0.is_finite() => {
                             if U {
                                 string.truncate(dot_pos);
                             } else if R > 0 {
@@ -443,18 +821,17 @@ macro_rules! impl_num_str {
                                 // do nothing
                             }
                         },
-                        Some(_) => {
-                            // is NOT finite, do nothing
-                        },
-                        None if (U || !self.0.is_finite()) => {
-                            // is not finite, or integer only, do nothing
+                        None if U => {
+                            // integer only, do nothing
                         },
                         None => {
+                            let dot_pos = string.len();
+
                             string.push(b'.');
                             if R > 0 {
-                                string.resize(original_len + R + 1, b'0');
-                            } else if M > 0 {
-                                string.resize(original_len + M + 1, b'0');
+                                string.resize(dot_pos + R + 1, b'0');
+                            } else if dot_pos - original_len < M {
+                                string.resize(dot_pos + M + 1, b'0');
                             } else {
                                 string.push(b'0');
                             }
@@ -463,103 +840,75 @@ macro_rules! impl_num_str {
                 }
 
                 #[inline]
+                #[deprecated(since = "0.8.3", note = "Use `encode` instead")]
                 /// Encode the number to the str
-                pub fn encode_bytes(mut self, string: &mut bytes::BytesMut) {
-                    if U {
-                        self.0 = self.0.trunc();
-                    }
-
-                    let original_len = string.len();
-
-                    #[cfg(not(feature = "feat-string-ext-ryu"))]
-                    string.extend(format!("{}", self.0).as_bytes());
-
-                    #[cfg(feature = "feat-string-ext-ryu")]
-                    string.extend(ryu::Buffer::new().format(self.0).as_bytes());
-
-                    #[allow(unsafe_code)]
-                    match unsafe { std::str::from_utf8_unchecked(string) }.rfind('.') {
-                        Some(dot_pos) if self.0.is_finite() => {
-                            if U {
-                                string.truncate(dot_pos);
-                            } else if R > 0 {
-                                string.resize(dot_pos + R + 1, b'0');
-                            } else if dot_pos - original_len < M {
-                                string.resize(dot_pos + M + 1, b'0');
-                            } else {
-                                // do nothing
-                            }
-                        },
-                        Some(_) => {
-                            // is NOT finite, do nothing
-                        },
-                        None if (U || !self.0.is_finite()) => {
-                            // is not finite, or integer only, do nothing
-                        },
-                        None => {
-                            string.extend(b".");
-                            if R > 0 {
-                                string.resize(original_len + R + 1, b'0');
-                            } else if M > 0 {
-                                string.resize(original_len + M + 1, b'0');
-                            } else {
-                                string.extend(b"0");
-                            }
-                        }
-                    }
+                pub fn encode_bytes(self, string: &mut bytes::BytesMut) {
+                    self.encode(string);
                 }
             }
 
-            impl_num_str!(@INTERNAL $ty);
+            impl_num_str!(@INTERNAL $ty, u8::is_ascii_digit);
         )*
     };
 
-    (@INTERNAL $ty:ty) => {
-        impl<const B: u8, const U: bool, const R: usize, const M: usize> StringT for NumStr<B, U, R, M, $ty> {
+    (@INTERNAL $ty:ty, $is_digit:path) => {
+        impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> StringT for Grouped<B, U, R, M, $ty, S> {
             #[inline]
-            fn encode_to_buf(self, string: &mut Vec<u8>) {
-                self.encode(string)
+            fn encode_to_buf<BUF: BufT>(self, string: &mut BUF) {
+                let start = string.len();
+
+                self.inner.encode(string);
+
+                self.grouping.group_in_place(string, start, $is_digit);
             }
 
             #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-                self.encode(string);
-                string.extend(separator.as_bytes());
+            fn len_hint(&self) -> (usize, Option<usize>) {
+                let (lower, upper) = self.inner.encoded_len_hint();
+                let separator_len = self.grouping.separator.len_utf8();
+
+                (
+                    lower,
+                    upper.and_then(|upper| {
+                        self.grouping
+                            .separators(upper)
+                            .checked_mul(separator_len)?
+                            .checked_add(upper)
+                    }),
+                )
             }
+        }
 
+        impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> StringExtT for Grouped<B, U, R, M, $ty, S> {}
+
+        impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> StringT for NumStr<B, U, R, M, $ty, S> {
             #[inline]
-            fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                self.encode_bytes(string)
+            fn encode_to_buf<BUF: BufT>(self, string: &mut BUF) {
+                self.encode(string)
             }
 
             #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                self.encode_bytes(string);
-                string.extend(separator.as_bytes());
+            fn len_hint(&self) -> (usize, Option<usize>) {
+                self.encoded_len_hint()
             }
         }
 
-        impl<const B: u8, const U: bool, const R: usize, const M: usize> StringExtT for NumStr<B, U, R, M, $ty> {}
+        impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> StringExtT for NumStr<B, U, R, M, $ty, S> {}
 
         impl StringT for $ty {
             #[inline]
-            fn encode_to_buf(self, string: &mut Vec<u8>) {
+            fn encode_to_buf<BUF: BufT>(self, string: &mut BUF) {
                 NumStr::new_default(self).encode_to_buf(string)
             }
 
             #[inline]
-            fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+            fn encode_to_buf_with_separator<BUF: BufT>(self, string: &mut BUF, separator: &mut Separator<'_>) {
                 NumStr::new_default(self).encode_to_buf_with_separator(string, separator)
             }
 
             #[inline]
-            fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-                NumStr::new_default(self).encode_to_bytes_buf(string)
-            }
-
-            #[inline]
-            fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-                NumStr::new_default(self).encode_to_bytes_buf_with_separator(string, separator)
+            fn len_hint(&self) -> (usize, Option<usize>) {
+                NumStr::new_default(*self).encoded_len_hint()
             }
         }
 
@@ -572,10 +921,211 @@ impl_num_str!(SIGNED: i8 as u8; i16 as u16; i32 as u32; i64 as u64; i128 as u128
 impl_num_str!(FLOAT: f32 f64);
 impl_for_shared_ref!(COPIED: u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
 
+// === parse ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Error returned when parsing a [`NumStr`].
+pub enum ParseError {
+    /// No digits, like `""`, `"-"` or `"0x"`.
+    Empty,
+
+    /// Invalid digit for the base, `-` for unsigned numbers, or a misplaced
+    /// separator.
+    InvalidDigit {
+        /// Byte offset of the invalid char in the input.
+        offset: usize,
+    },
+
+    /// The number does not fit in the target type.
+    Overflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("cannot parse number from string without digits"),
+            Self::InvalidDigit { offset } => write!(f, "invalid digit at offset {offset}"),
+            Self::Overflow => f.write_str("number too large or too small to fit in target type"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[inline]
+/// Strip the sign and the prefix (`0b`, `0o` or `0x`, matching the base), returns
+/// whether negative and the offset of the digits.
+fn strip_prefix<const B: u8>(input: &str, positive: &[u8]) -> (bool, usize) {
+    let input = input.as_bytes();
+
+    let (negative, mut offset) = match input.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ if !positive.is_empty() && input.starts_with(positive) => (false, positive.len()),
+        _ => (false, 0),
+    };
+
+    let prefix: &[u8] = match B {
+        2 => b"0b",
+        8 => b"0o",
+        16 => b"0x",
+        _ => return (negative, offset),
+    };
+
+    if input
+        .get(offset..offset + 2)
+        .is_some_and(|maybe_prefix| maybe_prefix.eq_ignore_ascii_case(prefix))
+    {
+        offset += 2;
+    }
+
+    (negative, offset)
+}
+
+#[inline]
+/// Returns the value of the digit, case-insensitive except for base 62.
+const fn digit_value<const B: u8>(c: char) -> Option<u8> {
+    let value = match c {
+        '0'..='9' => c as u8 - b'0',
+        'A'..='Z' => c as u8 - b'A' + 10,
+        'a'..='z' if B == 62 => c as u8 - b'a' + 36,
+        'a'..='z' => c as u8 - b'a' + 10,
+        _ => return None,
+    };
+
+    if value < B {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+macro_rules! impl_num_str_parse {
+    ($($ty:ty)*) => {
+        $(
+            impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> NumStr<B, U, R, M, $ty, S> {
+                /// Parse the number in base `B`, with the separator of the given [`Grouping`]
+                /// between digits skipped, see [`FromStr`] for details.
+                ///
+                /// ```rust
+                /// # use macro_toolset::string::{number::Grouping, NumStr, StringExtT};
+                /// let num = NumStr::new_default(-1234567_i32).grouped(Grouping::INDIAN);
+                /// assert_eq!(num.to_string_ext(), "-12,34,567");
+                /// assert_eq!(
+                ///     *NumStr::<10, false, 0, 0, i32>::parse_grouped("-12,34,567", Grouping::INDIAN).unwrap(),
+                ///     -1234567
+                /// );
+                /// ```
+                pub fn parse_grouped(input: &str, grouping: Grouping) -> Result<Self, ParseError> {
+                    Self::parse_with_separator(input, grouping.separator)
+                }
+
+                fn parse_with_separator(input: &str, separator: char) -> Result<Self, ParseError> {
+                    #[allow(clippy::let_unit_value)]
+                    let () = Self::BASE_CHECK;
+
+                    let (negative, offset) = strip_prefix::<B>(input, S::POSITIVE);
+
+                    #[allow(unused_comparisons)]
+                    if negative && <$ty>::MIN >= 0 {
+                        return Err(ParseError::InvalidDigit { offset: 0 });
+                    }
+
+                    let mut num: $ty = 0;
+                    let mut has_digit = false;
+                    let mut pending_separator = None;
+
+                    for (idx, c) in input[offset..].char_indices() {
+                        if c == separator {
+                            // Only a single separator between digits is allowed.
+                            if !has_digit || pending_separator.is_some() {
+                                return Err(ParseError::InvalidDigit { offset: offset + idx });
+                            }
+
+                            pending_separator = Some(offset + idx);
+                            continue;
+                        }
+
+                        let digit = digit_value::<B>(c)
+                            .ok_or(ParseError::InvalidDigit { offset: offset + idx })?;
+
+                        // Accumulate negatively for negative numbers, or `MIN` overflows.
+                        num = num
+                            .checked_mul(B as $ty)
+                            .and_then(|num| {
+                                if negative {
+                                    num.checked_sub(digit as $ty)
+                                } else {
+                                    num.checked_add(digit as $ty)
+                                }
+                            })
+                            .ok_or(ParseError::Overflow)?;
+
+                        has_digit = true;
+                        pending_separator = None;
+                    }
+
+                    if let Some(offset) = pending_separator {
+                        return Err(ParseError::InvalidDigit { offset });
+                    }
+
+                    if has_digit {
+                        Ok(NumStr(num, PhantomData))
+                    } else {
+                        Err(ParseError::Empty)
+                    }
+                }
+            }
+
+            impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> FromStr for NumStr<B, U, R, M, $ty, S> {
+                type Err = ParseError;
+
+                /// Parse the number in base `B`, the inverse of encoding.
+                ///
+                /// - Leading `-` (for signed numbers only), `+` or the sign of `S` is
+                ///   accepted.
+                /// - The prefix `0b`, `0o` or `0x` is accepted for base 2, 8 or 16.
+                /// - Digits are case-insensitive, except for base 62.
+                /// - A single `_` between digits is skipped, like Rust integer literals,
+                ///   while a leading, trailing or repeated one is an invalid digit. See
+                ///   [`NumStr::parse_grouped`] for other separators.
+                ///
+                /// ```rust
+                /// # use macro_toolset::string::{number::ParseError, NumStr};
+                /// type Hex = NumStr<16, false, 0, 0, u32>;
+                ///
+                /// assert_eq!(*"0xDEAD_beef".parse::<Hex>().unwrap(), 0xdeadbeef);
+                /// assert_eq!(*Hex::try_from("00ff").unwrap(), 0xff);
+                /// assert_eq!("1_0000_0000".parse::<Hex>(), Err(ParseError::Overflow));
+                /// assert_eq!("0xfg".parse::<Hex>(), Err(ParseError::InvalidDigit { offset: 3 }));
+                /// ```
+                fn from_str(input: &str) -> Result<Self, Self::Err> {
+                    Self::parse_with_separator(input, '_')
+                }
+            }
+
+            impl<const B: u8, const U: bool, const R: usize, const M: usize, S: SignT> TryFrom<&str> for NumStr<B, U, R, M, $ty, S> {
+                type Error = ParseError;
+
+                #[inline]
+                fn try_from(input: &str) -> Result<Self, Self::Error> {
+                    input.parse()
+                }
+            }
+        )*
+    };
+}
+
+impl_num_str_parse!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
 #[cfg(test)]
 #[allow(clippy::cognitive_complexity)]
 mod test {
-    use crate::string::{NumStr, StringExtT};
+    use super::{Grouping, ParseError, Precision, SignPlus, SignSpace};
+    use crate::{
+        str_concat,
+        string::{NumStr, PushAnyT, StringExtT, StringT},
+    };
 
     #[test]
     fn test_num_basic() {
@@ -663,22 +1213,10 @@ mod test {
         assert_eq!("-inf", f64::NEG_INFINITY.to_string_ext());
         assert_eq!("-1.0", (-1.0_f32).to_string_ext());
         assert_eq!("-1.0", (-1.0_f64).to_string_ext());
-        #[cfg(feature = "feat-string-ext-ryu")]
-        assert_eq!(
-            "-1.23e-40",
-            (-0.000000000000000000000000000000000000000123_f32).to_string_ext()
-        );
-        #[cfg(not(feature = "feat-string-ext-ryu"))]
         assert_eq!(
             "-0.000000000000000000000000000000000000000123",
             (-0.000000000000000000000000000000000000000123_f32).to_string_ext()
         );
-        #[cfg(feature = "feat-string-ext-ryu")]
-        assert_eq!(
-            "-1.23e-40",
-            (-0.000000000000000000000000000000000000000123_f64).to_string_ext()
-        );
-        #[cfg(not(feature = "feat-string-ext-ryu"))]
         assert_eq!(
             "-0.000000000000000000000000000000000000000123",
             (-0.000000000000000000000000000000000000000123_f64).to_string_ext()
@@ -1052,6 +1590,61 @@ mod test {
             "123456789.87654321"
         );
         assert_eq!(data.set_integer_only::<true>().to_string_ext(), "123456789");
+
+        let data = NumStr::new_default(12.0_f64);
+        assert_eq!(data.set_resize_len::<3>().to_string_ext(), "12.000");
+        assert_eq!(data.set_minimum_len::<3>().to_string_ext(), "12.000");
+
+        // Truncated, unlike `with_precision`.
+        let data = NumStr::new_default(1.99_f64);
+        assert_eq!(data.set_resize_len::<1>().to_string_ext(), "1.9");
+        assert_eq!(
+            data.with_precision(Precision::Decimals(1)).to_string_ext(),
+            "2.0"
+        );
+        assert_eq!(NumStr::new_default(0.1_f32 + 0.2).to_string_ext(), "0.3");
+        assert_eq!(
+            NumStr::new_default(0.1_f64 + 0.2).to_string_ext(),
+            "0.30000000000000004"
+        );
+
+        // Always positional, with or without `feat-string-ext-ryu`.
+        assert_eq!(1e-7_f64.to_string_ext(), "0.0000001");
+        assert_eq!(
+            NumStr::new_default(1e-7_f64)
+                .set_resize_len::<3>()
+                .to_string_ext(),
+            "0.000"
+        );
+        assert_eq!(
+            NumStr::new_default(1.5e-7_f64)
+                .set_resize_len::<8>()
+                .to_string_ext(),
+            "0.00000015"
+        );
+        let data = NumStr::new_default(-1.5e20_f64);
+        assert_eq!(
+            data.set_integer_only::<true>().to_string_ext(),
+            "-150000000000000000000"
+        );
+        assert_eq!(
+            data.set_minimum_len::<2>().to_string_ext(),
+            "-150000000000000000000.0"
+        );
+        let data = NumStr::new_default(5e-324_f64);
+        assert_eq!(data.set_resize_len::<3>().to_string_ext(), "0.000");
+        assert_eq!(data.to_string_ext(), format!("{}", 5e-324_f64));
+
+        for value in (0..1000)
+            .map(|_| f64::from_bits(rand::random::<u64>()))
+            .filter(|value| value.is_finite())
+        {
+            let mut expected = format!("{value}");
+            if !expected.contains('.') {
+                expected.push_str(".0");
+            }
+            assert_eq!(value.to_string_ext(), expected);
+        }
     }
 
     #[test]
@@ -1066,4 +1659,541 @@ mod test {
             "1234567890abc"
         );
     }
+
+    #[test]
+    fn test_num_decimal() {
+        for num in 0..=u16::MAX {
+            assert_eq!(num.to_string(), num.to_string_ext());
+        }
+
+        let mut num = 1_u128;
+        while let Some(next) = num.checked_mul(10) {
+            for num in [num - 1, num, num + 1, num * 5] {
+                assert_eq!(num.to_string(), num.to_string_ext());
+
+                if let Ok(num) = u64::try_from(num) {
+                    assert_eq!(num.to_string(), num.to_string_ext());
+                }
+            }
+            num = next;
+        }
+
+        for num in [u128::from(u64::MAX) + 1, (1 << 83) - 1, 1 << 83, u128::MAX]
+            .into_iter()
+            .chain((0..10000).map(|_| rand::random::<u128>() >> (rand::random::<u8>() % 64)))
+        {
+            assert_eq!(num.to_string(), num.to_string_ext());
+        }
+        assert_eq!(
+            "00000000000000000000000000000000000000001",
+            NumStr::new_default(1_u128)
+                .set_minimum_len::<41>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "0000012345",
+            NumStr::new_default(12345_u64)
+                .set_minimum_len::<10>()
+                .to_string_ext()
+        );
+    }
+
+    #[test]
+    fn test_num_grouped() {
+        let thousands = |num: i64| NumStr::new_default(num).grouped(Grouping::THOUSANDS);
+
+        assert_eq!(thousands(0).to_string_ext(), "0");
+        assert_eq!(thousands(999).to_string_ext(), "999");
+        assert_eq!(thousands(1000).to_string_ext(), "1,000");
+        assert_eq!(thousands(-100000).to_string_ext(), "-100,000");
+        assert_eq!(
+            thousands(i64::MIN).to_string_ext(),
+            "-9,223,372,036,854,775,808"
+        );
+        assert_eq!(
+            NumStr::new_default(u128::MAX)
+                .grouped(Grouping::UNDERSCORE)
+                .to_string_ext(),
+            "340_282_366_920_938_463_463_374_607_431_768_211_455"
+        );
+
+        // Indian style, and custom group sizes.
+        let num = NumStr::new_default(1234567890_u64);
+        assert_eq!(
+            num.grouped(Grouping::INDIAN).to_string_ext(),
+            "1,23,45,67,890"
+        );
+        assert_eq!(
+            num.grouped(Grouping::new(0, ',')).to_string_ext(),
+            "1234567890"
+        );
+        assert_eq!(
+            num.grouped(Grouping::new(0, ',').first_size(3))
+                .to_string_ext(),
+            "1234567,890"
+        );
+        assert_eq!(
+            num.grouped(Grouping::new(3, '\u{202F}')).to_string_ext(),
+            "1\u{202F}234\u{202F}567\u{202F}890"
+        );
+
+        // With other bases, `R` and `M`.
+        assert_eq!(
+            NumStr::hex_default(0xdeadbeef_u32)
+                .grouped(Grouping::new(4, '_'))
+                .to_string_ext(),
+            "dead_beef"
+        );
+        assert_eq!(
+            NumStr::hex_default(0xabc_u16)
+                .set_resize_len::<8>()
+                .grouped(Grouping::new(4, '_'))
+                .to_string_ext(),
+            "0000_0abc"
+        );
+        assert_eq!(
+            NumStr::new_default(1234_u32)
+                .set_minimum_len::<7>()
+                .grouped(Grouping::THOUSANDS)
+                .to_string_ext(),
+            "0,001,234"
+        );
+
+        // Floats, only the integer part.
+        let float = |num: f64| NumStr::new_default(num).grouped(Grouping::THOUSANDS);
+        assert_eq!(float(1234567.25).to_string_ext(), "1,234,567.25");
+        assert_eq!(float(-1234.5).to_string_ext(), "-1,234.5");
+        assert_eq!(float(123.0).to_string_ext(), "123.0");
+        assert_eq!(float(f64::NAN).to_string_ext(), "NaN");
+        assert_eq!(
+            NumStr::new_default(1234.5_f32)
+                .set_resize_len::<3>()
+                .grouped(Grouping::THOUSANDS)
+                .to_string_ext(),
+            "1,234.500"
+        );
+        assert_eq!(
+            NumStr::new_default(1234.5_f32)
+                .set_integer_only::<true>()
+                .grouped(Grouping::THOUSANDS)
+                .to_string_ext(),
+            "1,234"
+        );
+
+        // Existing content is untouched, and the length hint is an upper bound.
+        let grouped = NumStr::new_default(-1234567_i32).grouped(Grouping::new(2, '·'));
+        let mut string = "-1".to_string();
+        string.push_any(grouped);
+        assert_eq!(string, "-1-1·23·45·67");
+        assert!(grouped.len_hint().1.is_some_and(|upper| upper >= 13));
+        assert_eq!(
+            str_concat!(sep = ", "; grouped, 12345_u16),
+            "-1·23·45·67, 12345"
+        );
+    }
+
+    #[test]
+    fn test_num_sign() {
+        macro_rules! test_signed {
+            ($($ty:ty)*) => {
+                $(
+                    let num = |num: $ty| NumStr::new_default(num).set_minimum_len::<4>();
+
+                    assert_eq!(num(-42).to_string_ext(), "-0042");
+                    assert_eq!(num(42).to_string_ext(), "0042");
+                    assert_eq!(num(0).set_sign::<SignPlus>().to_string_ext(), "+0000");
+                    assert_eq!(num(42).set_sign::<SignPlus>().to_string_ext(), "+0042");
+                    assert_eq!(num(-42).set_sign::<SignPlus>().to_string_ext(), "-0042");
+                    assert_eq!(num(42).set_sign::<SignSpace>().to_string_ext(), " 0042");
+                    assert_eq!(num(-42).set_sign::<SignSpace>().to_string_ext(), "-0042");
+                    assert_eq!(
+                        NumStr::new_default(<$ty>::MIN).set_sign::<SignPlus>().to_string_ext(),
+                        <$ty>::MIN.to_string()
+                    );
+                    assert_eq!(
+                        NumStr::new_default(<$ty>::MAX).set_sign::<SignPlus>().to_string_ext(),
+                        format!("{:+}", <$ty>::MAX)
+                    );
+                    assert_eq!(
+                        NumStr::new_default(-0x7b as $ty)
+                            .hexadecimal()
+                            .set_resize_len::<4>()
+                            .to_string_ext(),
+                        "-007b"
+                    );
+                    assert_eq!(
+                        NumStr::new_default(-123 as $ty).set_resize_len::<2>().to_string_ext(),
+                        "-23"
+                    );
+                )*
+            };
+        }
+
+        test_signed!(i8 i16 i32 i64 i128 isize);
+
+        // Unsigned numbers are never negative.
+        assert_eq!(
+            NumStr::new_default(42_u8)
+                .set_sign::<SignPlus>()
+                .to_string_ext(),
+            "+42"
+        );
+        assert_eq!(
+            NumStr::new_default(42_u128)
+                .set_minimum_len::<4>()
+                .set_sign::<SignSpace>()
+                .to_string_ext(),
+            " 0042"
+        );
+
+        // Floats, `R` and `M` are for the fraction part.
+        let float = |num: f64| NumStr::new_default(num).set_sign::<SignPlus>();
+        assert_eq!(float(1.5).to_string_ext(), "+1.5");
+        assert_eq!(float(-1.5).to_string_ext(), "-1.5");
+        assert_eq!(float(0.0).to_string_ext(), "+0.0");
+        assert_eq!(float(-0.0).to_string_ext(), "-0.0");
+        assert_eq!(float(f64::INFINITY).to_string_ext(), "+inf");
+        assert_eq!(float(f64::NEG_INFINITY).to_string_ext(), "-inf");
+        assert_eq!(float(f64::NAN).to_string_ext(), "NaN");
+        assert_eq!(float(-1.5).set_resize_len::<3>().to_string_ext(), "-1.500");
+        assert_eq!(
+            NumStr::new_default(1.5_f32)
+                .set_sign::<SignSpace>()
+                .to_string_ext(),
+            " 1.5"
+        );
+        assert_eq!(
+            NumStr::new_default(-12.5_f32)
+                .set_minimum_len::<3>()
+                .to_string_ext(),
+            "-12.500"
+        );
+        assert_eq!(
+            NumStr::new_default(-12.5_f32)
+                .set_integer_only::<true>()
+                .set_sign::<SignSpace>()
+                .to_string_ext(),
+            "-12"
+        );
+
+        // Exact length hint, and composes with grouping.
+        let num = NumStr::new_default(-42_i64)
+            .set_minimum_len::<4>()
+            .set_sign::<SignPlus>();
+        assert_eq!(num.len_hint(), (5, Some(5)));
+        assert_eq!(
+            NumStr::new_default(42_u32)
+                .set_sign::<SignSpace>()
+                .len_hint(),
+            (3, Some(3))
+        );
+        assert_eq!(num.grouped(Grouping::THOUSANDS).to_string_ext(), "-0,042");
+        assert_eq!(
+            NumStr::new_default(1234567_i32)
+                .set_sign::<SignPlus>()
+                .grouped(Grouping::THOUSANDS)
+                .to_string_ext(),
+            "+1,234,567"
+        );
+    }
+
+    #[test]
+    fn test_num_custom_base() {
+        assert_eq!(
+            "1111011",
+            NumStr::new_default(123_u8)
+                .set_custom_base::<2>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "11120",
+            NumStr::new_default(123_u8)
+                .set_custom_base::<3>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "z",
+            NumStr::new_default(35_u8)
+                .set_custom_base::<36>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "10",
+            NumStr::new_default(36_u8)
+                .set_custom_base::<36>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "3W5E11264SGSF",
+            NumStr::new_default(u64::MAX)
+                .set_custom_base::<36>()
+                .set_uppercase::<true>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "-1y2p0ij32e8e8",
+            NumStr::new_default(i64::MIN)
+                .set_custom_base::<36>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "f5lxx1zz5pnorynqglhzmsp33",
+            NumStr::new_default(u128::MAX)
+                .set_custom_base::<36>()
+                .to_string_ext()
+        );
+
+        // Base 62, `U` makes no sense.
+        assert_eq!(
+            "z",
+            NumStr::new_default(61_u8)
+                .set_custom_base::<62>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "LygHa16AHYF",
+            NumStr::new_default(u64::MAX)
+                .set_custom_base::<62>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "LygHa16AHYF",
+            NumStr::new_default(u64::MAX)
+                .set_custom_base::<62>()
+                .set_uppercase::<true>()
+                .to_string_ext()
+        );
+        assert_eq!(
+            "0000001z",
+            NumStr::new_default(123_u32)
+                .set_custom_base::<62>()
+                .set_resize_len::<8>()
+                .to_string_ext()
+        );
+    }
+
+    #[test]
+    fn test_len_hint() {
+        macro_rules! assert_exact_len_hint {
+            ($($num:expr),+) => {
+                $(
+                    let len = $num.to_string_ext().len();
+                    assert_eq!($num.len_hint(), (len, Some(len)), "{}", stringify!($num));
+                )+
+            };
+        }
+
+        assert_exact_len_hint!(
+            0_u8,
+            9_u8,
+            10_u8,
+            u8::MAX,
+            u64::MAX,
+            u128::MAX,
+            i8::MIN,
+            -1_i32,
+            i128::MIN,
+            NumStr::new_default(0x0_u32).hexadecimal(),
+            NumStr::new_default(0x10_u32).hexadecimal(),
+            NumStr::new_default(u64::MAX).hexadecimal(),
+            NumStr::new_default(-0x10_i64).hexadecimal(),
+            NumStr::new_default(123_u16).set_custom_base::<2>(),
+            NumStr::new_default(u64::MAX).set_custom_base::<36>(),
+            NumStr::new_default(u128::MAX).set_custom_base::<62>(),
+            NumStr::new_default(123_u16).set_resize_len::<2>(),
+            NumStr::new_default(123_u16).set_resize_len::<8>(),
+            NumStr::new_default(123_u16).set_minimum_len::<2>(),
+            NumStr::new_default(123_u16).set_minimum_len::<8>()
+        );
+    }
+
+    #[test]
+    fn test_num_parse() {
+        macro_rules! assert_round_trip {
+            ($($ty:ident),+) => {
+                $(
+                    for num in [$ty::MIN, $ty::MAX, 0, 1, rand::random::<$ty>()] {
+                        let decimal = NumStr::new_default(num);
+                        assert_eq!(*decimal.to_string_ext().parse::<NumStr<10, false, 0, 0, $ty>>().unwrap(), num);
+
+                        let hex = NumStr::new_default(num).hexadecimal().set_uppercase::<true>();
+                        assert_eq!(*hex.to_string_ext().parse::<NumStr<16, false, 0, 0, $ty>>().unwrap(), num);
+
+                        let base36 = NumStr::new_default(num).set_custom_base::<36>();
+                        assert_eq!(*base36.to_string_ext().parse::<NumStr<36, false, 0, 0, $ty>>().unwrap(), num);
+
+                        let base62 = NumStr::new_default(num).set_custom_base::<62>();
+                        assert_eq!(*base62.to_string_ext().parse::<NumStr<62, false, 0, 0, $ty>>().unwrap(), num);
+
+                        let padded = NumStr::new_default(num).set_custom_base::<2>().set_minimum_len::<200>();
+                        assert_eq!(*padded.to_string_ext().parse::<NumStr<2, false, 0, 0, $ty>>().unwrap(), num);
+                    }
+                )+
+            };
+        }
+
+        assert_round_trip!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+        type Dec = NumStr<10, false, 0, 0, i32>;
+        type Hex = NumStr<16, false, 0, 0, u64>;
+
+        assert_eq!(*"+42".parse::<Dec>().unwrap(), 42);
+        assert_eq!(*"-0".parse::<Dec>().unwrap(), 0);
+        assert_eq!(*"1_000_000".parse::<Dec>().unwrap(), 1_000_000);
+        assert_eq!(*Dec::try_from("-2147483648").unwrap(), i32::MIN);
+        assert_eq!(
+            *" 42"
+                .parse::<NumStr<10, false, 0, 0, i32, SignSpace>>()
+                .unwrap(),
+            42
+        );
+
+        // Prefix, only for the matching base.
+        assert_eq!(*"0xDeadBeef".parse::<Hex>().unwrap(), 0xdead_beef);
+        assert_eq!(*"0XFF".parse::<Hex>().unwrap(), 0xff);
+        assert_eq!(
+            *"-0x80".parse::<NumStr<16, false, 0, 0, i8>>().unwrap(),
+            i8::MIN
+        );
+        assert_eq!(
+            *"0b1010".parse::<NumStr<2, false, 0, 0, u8>>().unwrap(),
+            0b1010
+        );
+        assert_eq!(
+            *"0o777".parse::<NumStr<8, false, 0, 0, u16>>().unwrap(),
+            0o777
+        );
+        assert_eq!(*"0x".parse::<NumStr<36, false, 0, 0, u64>>().unwrap(), 33);
+        assert_eq!(
+            "0x10".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 1 })
+        );
+
+        // Base 62 is case-sensitive.
+        assert_eq!(
+            *"aA".parse::<NumStr<62, false, 0, 0, u32>>().unwrap(),
+            36 * 62 + 10
+        );
+        assert_eq!(
+            *"aA".parse::<NumStr<36, false, 0, 0, u32>>().unwrap(),
+            10 * 36 + 10
+        );
+
+        // Errors
+        assert_eq!("".parse::<Dec>(), Err(ParseError::Empty));
+        assert_eq!("-".parse::<Dec>(), Err(ParseError::Empty));
+        assert_eq!("0x".parse::<Hex>(), Err(ParseError::Empty));
+        assert_eq!(
+            "_".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 0 })
+        );
+        assert_eq!(
+            "___".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 0 })
+        );
+        assert_eq!(
+            "_1".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 0 })
+        );
+        assert_eq!(
+            "-_1".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 1 })
+        );
+        assert_eq!(
+            "1__0".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 2 })
+        );
+        assert_eq!(
+            "1_".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 1 })
+        );
+        assert_eq!(
+            "0x_ff".parse::<Hex>(),
+            Err(ParseError::InvalidDigit { offset: 2 })
+        );
+        assert_eq!(
+            "12a".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 2 })
+        );
+        assert_eq!(
+            "-1".parse::<NumStr<10, false, 0, 0, u32>>(),
+            Err(ParseError::InvalidDigit { offset: 0 })
+        );
+        assert_eq!(
+            "1 2".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 1 })
+        );
+        assert_eq!(
+            "你1".parse::<Dec>(),
+            Err(ParseError::InvalidDigit { offset: 0 })
+        );
+        assert_eq!(
+            "12".parse::<NumStr<2, false, 0, 0, u8>>(),
+            Err(ParseError::InvalidDigit { offset: 1 })
+        );
+        assert_eq!(
+            "256".parse::<NumStr<10, false, 0, 0, u8>>(),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            "-129".parse::<NumStr<10, false, 0, 0, i8>>(),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!("2147483648".parse::<Dec>(), Err(ParseError::Overflow));
+        assert_eq!(
+            "0x1_0000_0000_0000_0000".parse::<Hex>(),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            ParseError::InvalidDigit { offset: 2 }.to_string(),
+            "invalid digit at offset 2"
+        );
+    }
+
+    #[test]
+    fn test_num_parse_grouped() {
+        type Dec = NumStr<10, false, 0, 0, i64>;
+
+        for grouping in [
+            Grouping::THOUSANDS,
+            Grouping::UNDERSCORE,
+            Grouping::INDIAN,
+            Grouping::new(4, ' '),
+        ] {
+            for num in [0, -1, 1234567, i64::MIN, i64::MAX, rand::random()] {
+                let string = NumStr::new_default(num).grouped(grouping).to_string_ext();
+                assert_eq!(
+                    *Dec::parse_grouped(&string, grouping).unwrap(),
+                    num,
+                    "{string}"
+                );
+            }
+        }
+
+        let hex = NumStr::new_default(0xdead_beef_u32)
+            .hexadecimal()
+            .grouped(Grouping::new(4, '_'));
+        assert_eq!(hex.to_string_ext(), "dead_beef");
+        assert_eq!(
+            *"dead_beef".parse::<NumStr<16, false, 0, 0, u32>>().unwrap(),
+            0xdead_beef
+        );
+
+        assert_eq!(
+            Dec::parse_grouped("1_234", Grouping::THOUSANDS),
+            Err(ParseError::InvalidDigit { offset: 1 })
+        );
+        assert_eq!(
+            Dec::parse_grouped(",", Grouping::THOUSANDS),
+            Err(ParseError::InvalidDigit { offset: 0 })
+        );
+        assert_eq!(
+            Dec::parse_grouped("1,,234", Grouping::THOUSANDS),
+            Err(ParseError::InvalidDigit { offset: 2 })
+        );
+        assert_eq!(
+            Dec::parse_grouped("1 234 ", Grouping::new(3, ' ')),
+            Err(ParseError::InvalidDigit { offset: 5 })
+        );
+    }
 }
@@ -0,0 +1,813 @@
+//! Float number with given precision and notation, see [`FloatStr`].
+
+use std::{cmp::Ordering, marker::PhantomData};
+
+mod shortest;
+
+pub(super) use self::shortest::{Shortest, MAX_LEN as MAX_SHORTEST_LEN};
+use super::{write_decimal_u64, NumStr, SignMinus, SignT};
+use crate::string::{BufT, StringExtT, StringT};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Precision of [`FloatStr`].
+pub enum Precision {
+    /// Digits after the decimal point, of the mantissa in scientific or
+    /// engineering notation.
+    Decimals(usize),
+
+    /// Significant digits, at least 1.
+    Significant(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Notation of [`FloatStr`].
+pub enum Notation {
+    #[default]
+    /// Like `1234.5`.
+    Positional,
+
+    /// Like `1.2345e3`, the same as `format!("{:e}", ...)`.
+    Scientific,
+
+    /// Like `1.2345e3` or `12.345e-6`, the exponent is a multiple of 3.
+    Engineering,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Float number rounded to the given [`Precision`], written in the given
+/// [`Notation`].
+///
+/// The exact binary value is rounded half to even, e.g. `0.125` is rounded to
+/// `0.12` and `2.5` to `2`, while `1.005` (`1.00499999999999989...` actually)
+/// is rounded to `1.00`, like `format!("{:.2}", ...)` but without `format!`.
+///
+/// `NaN` and infinities are written as `NaN`, `inf` and `-inf`.
+///
+/// # Example
+///
+/// ```rust
+/// # use macro_toolset::string::{number::{Notation, Precision}, NumStr, StringExtT};
+/// let num = NumStr::new_default(1234.5678_f64);
+/// assert_eq!(num.with_precision(Precision::Decimals(2)).to_string_ext(), "1234.57");
+/// assert_eq!(num.with_precision(Precision::Significant(2)).to_string_ext(), "1200");
+/// assert_eq!(
+///     num.with_precision(Precision::Decimals(2))
+///         .notation(Notation::Scientific)
+///         .to_string_ext(),
+///     "1.23e3"
+/// );
+/// assert_eq!(
+///     NumStr::new_default(0.000012345_f64)
+///         .with_precision(Precision::Significant(3))
+///         .notation(Notation::Engineering)
+///         .to_string_ext(),
+///     "12.3e-6"
+/// );
+/// ```
+pub struct FloatStr<T = f64, S = SignMinus> {
+    value: T,
+    precision: Precision,
+    notation: Notation,
+    sign: PhantomData<S>,
+}
+
+impl<T> FloatStr<T> {
+    #[inline]
+    /// Create a new [`FloatStr`] with the given precision, in positional
+    /// notation.
+    pub const fn new(value: T, precision: Precision) -> Self {
+        Self {
+            value,
+            precision,
+            notation: Notation::Positional,
+            sign: PhantomData,
+        }
+    }
+}
+
+impl<T, S> FloatStr<T, S> {
+    #[inline]
+    /// Set the notation.
+    pub const fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    #[inline]
+    /// Set the sign, see [`SignT`].
+    pub fn set_sign<NS>(self) -> FloatStr<T, NS> {
+        FloatStr {
+            value: self.value,
+            precision: self.precision,
+            notation: self.notation,
+            sign: PhantomData,
+        }
+    }
+}
+
+macro_rules! impl_float_str {
+    ($($ty:ty)*) => {
+        $(
+            impl<const B: u8, const U: bool, const R: usize, const M: usize, S> NumStr<B, U, R, M, $ty, S> {
+                #[inline]
+                /// Round to the given precision, see [`FloatStr`].
+                ///
+                /// `B`, `U`, `R` and `M` are ignored, while the sign is kept.
+                pub fn with_precision(self, precision: Precision) -> FloatStr<$ty, S> {
+                    FloatStr {
+                        value: self.0,
+                        precision,
+                        notation: Notation::Positional,
+                        sign: PhantomData,
+                    }
+                }
+            }
+
+            impl<S: SignT> StringT for FloatStr<$ty, S> {
+                #[inline]
+                fn encode_to_buf<BUF: BufT>(self, string: &mut BUF) {
+                    encode::<BUF, S>(string, f64::from(self.value), self.precision, self.notation);
+                }
+
+                #[inline]
+                fn len_hint(&self) -> (usize, Option<usize>) {
+                    (1, None)
+                }
+            }
+
+            impl<S: SignT> StringExtT for FloatStr<$ty, S> {}
+        )*
+    };
+}
+
+impl_float_str!(f32 f64);
+
+fn encode<BUF: BufT, S: SignT>(
+    string: &mut BUF,
+    value: f64,
+    precision: Precision,
+    notation: Notation,
+) {
+    if value.is_nan() {
+        string.extend_from_slice(b"NaN");
+        return;
+    }
+
+    if value.is_sign_negative() {
+        string.push(b'-');
+    } else {
+        string.extend_from_slice(S::POSITIVE);
+    }
+
+    if value.is_infinite() {
+        string.extend_from_slice(b"inf");
+    } else {
+        Digits::new(value.abs(), precision, notation).encode(string);
+    }
+}
+
+/// The max significant digits of the exact decimal value of a `f64`.
+const MAX_DIGITS: usize = 767;
+
+/// Rounded significant digits of a finite non-negative float, the value is
+/// `0.DIGITS * 10^exp10`, digits after `len` are all zero.
+struct Digits {
+    /// With room for a chunk of 9 digits.
+    buf: [u8; MAX_DIGITS + 9],
+    len: usize,
+    exp10: isize,
+    precision: Precision,
+    notation: Notation,
+}
+
+impl Digits {
+    /// Rounded half to even.
+    fn new(value: f64, precision: Precision, notation: Notation) -> Self {
+        let mut this = Self {
+            buf: [b'0'; MAX_DIGITS + 9],
+            len: 0,
+            exp10: 1,
+            precision,
+            notation,
+        };
+
+        if value == 0.0 {
+            return this;
+        }
+
+        // The value is `int + frac / 2^k`, with `frac < 2^k`.
+        let (mantissa, exp) = decode(value);
+        let (int, mut frac, k) = match usize::try_from(-exp) {
+            Err(_) => {
+                let mut int = Big::from_u64(mantissa);
+                int.shl(exp.unsigned_abs() as usize);
+                (int, Big::from_u64(0), 0)
+            }
+            Ok(k) if k < 64 => (
+                Big::from_u64(mantissa >> k),
+                Big::from_u64(mantissa & ((1 << k) - 1)),
+                k,
+            ),
+            Ok(k) => (Big::from_u64(0), Big::from_u64(mantissa), k),
+        };
+
+        this.len = int.write_decimal(&mut this.buf);
+
+        if this.len > 0 {
+            this.exp10 = this.len as isize;
+        } else {
+            // Skip the leading zeros of the fraction part.
+            this.exp10 = 0;
+
+            while frac.bit_len() + 30 <= k {
+                frac.mul_small(1_000_000_000);
+                this.exp10 -= 9;
+            }
+
+            loop {
+                frac.mul_small(10);
+
+                match frac.take_high(k) {
+                    0 => this.exp10 -= 1,
+                    digit => {
+                        this.buf[0] = b'0' + digit as u8;
+                        this.len = 1;
+                        break;
+                    }
+                }
+            }
+        }
+
+        let keep = this.keep();
+
+        let rest = match usize::try_from(keep) {
+            Err(_) => {
+                this.len = 0;
+                Ordering::Less
+            }
+            Ok(keep) if keep < this.len => {
+                // Within the integer part (or the first digit).
+                let rest = match this.buf[keep].cmp(&b'5') {
+                    Ordering::Equal
+                        if !frac.is_zero()
+                            || this.buf[keep + 1..this.len].iter().any(|&d| d != b'0') =>
+                    {
+                        Ordering::Greater
+                    }
+                    rest => rest,
+                };
+
+                this.buf[keep..this.len].fill(b'0');
+                this.len = keep;
+
+                rest
+            }
+            Ok(keep) => {
+                while this.len < keep && !frac.is_zero() {
+                    if keep - this.len >= 9 {
+                        frac.mul_small(1_000_000_000);
+                        write_decimal_u64(
+                            u64::from(frac.take_high(k)),
+                            &mut this.buf[this.len..this.len + 9],
+                        );
+                        this.len += 9;
+                    } else {
+                        frac.mul_small(10);
+                        this.buf[this.len] = b'0' + frac.take_high(k) as u8;
+                        this.len += 1;
+                    }
+                }
+
+                frac.cmp_half(k)
+            }
+        };
+
+        let round_up = match rest {
+            Ordering::Greater => true,
+            // Round half to even.
+            Ordering::Equal => this.len > 0 && (this.buf[this.len - 1] - b'0') % 2 == 1,
+            Ordering::Less => false,
+        };
+
+        if round_up {
+            this.round_up();
+        }
+
+        this
+    }
+
+    /// How many significant digits to keep.
+    fn keep(&self) -> isize {
+        match (self.precision, self.notation) {
+            (Precision::Significant(digits), _) => digits.max(1) as isize,
+            (Precision::Decimals(decimals), Notation::Positional) => self.exp10 + decimals as isize,
+            (Precision::Decimals(decimals), Notation::Scientific) => 1 + decimals as isize,
+            (Precision::Decimals(decimals), Notation::Engineering) => {
+                self.int_len() + decimals as isize
+            }
+        }
+    }
+
+    /// The exponent for scientific or engineering notation.
+    fn exponent(&self) -> isize {
+        match self.notation {
+            Notation::Engineering => (self.exp10 - 1).div_euclid(3) * 3,
+            _ => self.exp10 - 1,
+        }
+    }
+
+    /// The length of the integer part (of the mantissa).
+    fn int_len(&self) -> isize {
+        match self.notation {
+            Notation::Positional => self.exp10,
+            _ => self.exp10 - self.exponent(),
+        }
+    }
+
+    fn round_up(&mut self) {
+        while let Some(last) = self.len.checked_sub(1) {
+            if self.buf[last] == b'9' {
+                self.buf[last] = b'0';
+                self.len = last;
+            } else {
+                self.buf[last] += 1;
+                return;
+            }
+        }
+
+        // All nines, like `99.9` to `100`.
+        self.buf[0] = b'1';
+        self.len = 1;
+        self.exp10 += 1;
+    }
+
+    fn encode<BUF: BufT>(&self, string: &mut BUF) {
+        let int_len = self.int_len();
+
+        let frac_len = match self.precision {
+            Precision::Decimals(decimals) => decimals as isize,
+            Precision::Significant(digits) => (digits.max(1) as isize - int_len).max(0),
+        };
+
+        if int_len > 0 {
+            self.encode_digits(string, 0, int_len);
+        } else {
+            string.push(b'0');
+        }
+
+        if frac_len > 0 {
+            string.push(b'.');
+            self.encode_digits(string, int_len, int_len + frac_len);
+        }
+
+        if self.notation != Notation::Positional {
+            string.push(b'e');
+            NumStr::new_default(self.exponent()).encode(string);
+        }
+    }
+
+    /// Write the digits within `from..to`, which may be out of `0..len` and
+    /// filled with `0`.
+    fn encode_digits<BUF: BufT>(&self, string: &mut BUF, from: isize, to: isize) {
+        let len = self.len as isize;
+
+        let zeros_before = (to.min(0) - from).max(0);
+        let (start, end) = (from.clamp(0, len), to.clamp(0, len));
+        let zeros_after = to - from - zeros_before - (end - start);
+
+        string.resize(string.len() + zeros_before as usize, b'0');
+        string.extend_from_slice(&self.buf[start as usize..end as usize]);
+        string.resize(string.len() + zeros_after as usize, b'0');
+    }
+}
+
+#[inline]
+/// Returns the mantissa and exponent, `value = mantissa * 2^exp`.
+fn decode(value: f64) -> (u64, i32) {
+    let bits = value.to_bits();
+    let exp_bits = ((bits >> 52) & 0x7FF) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+
+    let (mantissa, exp) = if exp_bits == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), exp_bits - 1075)
+    };
+
+    // Smaller numbers for the following calculation.
+    let zeros = mantissa.trailing_zeros();
+
+    (mantissa >> zeros, exp + zeros as i32)
+}
+
+/// Limbs of [`Big`], enough for `2^1078`.
+const LIMBS: usize = 36;
+
+#[derive(Clone, Copy)]
+/// Minimal unsigned big integer, little endian.
+struct Big {
+    limbs: [u32; LIMBS],
+    len: usize,
+}
+
+impl Big {
+    const fn from_u64(num: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = num as u32;
+        limbs[1] = (num >> 32) as u32;
+
+        let len = match num {
+            0 => 0,
+            1..=0xFFFF_FFFF => 1,
+            _ => 2,
+        };
+
+        Self { limbs, len }
+    }
+
+    const fn is_zero(&self) -> bool {
+        self.len == 0
+    }
+
+    const fn bit_len(&self) -> usize {
+        match self.len {
+            0 => 0,
+            len => len * 32 - self.limbs[len - 1].leading_zeros() as usize,
+        }
+    }
+
+    fn normalize(&mut self) {
+        while self.len > 0 && self.limbs[self.len - 1] == 0 {
+            self.len -= 1;
+        }
+    }
+
+    fn shl(&mut self, bits: usize) {
+        if self.is_zero() {
+            return;
+        }
+
+        let (limb_shift, bit_shift) = (bits / 32, bits % 32);
+
+        self.limbs[self.len + limb_shift] = 0;
+
+        for idx in (0..self.len).rev() {
+            let shifted = u64::from(self.limbs[idx]) << bit_shift;
+            self.limbs[idx + limb_shift + 1] |= (shifted >> 32) as u32;
+            self.limbs[idx + limb_shift] = shifted as u32;
+        }
+
+        self.limbs[..limb_shift].fill(0);
+        self.len += limb_shift + 1;
+        self.normalize();
+    }
+
+    fn mul_small(&mut self, num: u32) {
+        let mut carry = 0;
+
+        for limb in &mut self.limbs[..self.len] {
+            let product = u64::from(*limb) * u64::from(num) + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+
+        if carry > 0 {
+            self.limbs[self.len] = carry as u32;
+            self.len += 1;
+        }
+    }
+
+    /// Divide by `num`, returns the remainder.
+    fn div_rem_small(&mut self, num: u32) -> u32 {
+        let mut rem = 0;
+
+        for limb in self.limbs[..self.len].iter_mut().rev() {
+            let dividend = (rem << 32) | u64::from(*limb);
+            *limb = (dividend / u64::from(num)) as u32;
+            rem = dividend % u64::from(num);
+        }
+
+        self.normalize();
+
+        rem as u32
+    }
+
+    /// Take the bits from `k`, which should fit in `u32`, and keep the lower
+    /// `k` bits.
+    fn take_high(&mut self, k: usize) -> u32 {
+        let (idx, shift) = (k / 32, k % 32);
+
+        if idx >= self.len {
+            return 0;
+        }
+
+        let mut high = u64::from(self.limbs[idx]) >> shift;
+        if idx + 1 < self.len {
+            high |= u64::from(self.limbs[idx + 1]) << (32 - shift);
+        }
+
+        self.limbs[idx] &= (1 << shift) - 1;
+        self.limbs[idx + 1..self.len].fill(0);
+        self.len = idx + 1;
+        self.normalize();
+
+        high as u32
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        self.len.cmp(&other.len).then_with(|| {
+            self.limbs[..self.len]
+                .iter()
+                .rev()
+                .cmp(other.limbs[..other.len].iter().rev())
+        })
+    }
+
+    fn added(mut self, other: &Self) -> Self {
+        let mut carry = 0;
+
+        for idx in 0..self.len.max(other.len) {
+            let sum = u64::from(self.limbs[idx]) + u64::from(other.limbs[idx]) + carry;
+            self.limbs[idx] = sum as u32;
+            carry = sum >> 32;
+        }
+
+        self.len = self.len.max(other.len);
+
+        if carry > 0 {
+            self.limbs[self.len] = carry as u32;
+            self.len += 1;
+        }
+
+        self
+    }
+
+    /// Subtract `other`, which should be no more than `self`.
+    fn sub(&mut self, other: &Self) {
+        let mut borrow = false;
+
+        for idx in 0..self.len {
+            let (diff, borrow_a) = self.limbs[idx].overflowing_sub(other.limbs[idx]);
+            let (diff, borrow_b) = diff.overflowing_sub(u32::from(borrow));
+            self.limbs[idx] = diff;
+            borrow = borrow_a || borrow_b;
+        }
+
+        self.normalize();
+    }
+
+    fn mul_pow10(&mut self, mut exp: usize) {
+        while exp >= 9 {
+            self.mul_small(1_000_000_000);
+            exp -= 9;
+        }
+
+        self.mul_small(10u32.pow(exp as u32));
+    }
+
+    /// Compare with `2^(k - 1)`, i.e. the half when as the fraction part.
+    fn cmp_half(&self, k: usize) -> Ordering {
+        if self.is_zero() {
+            return Ordering::Less;
+        }
+
+        let (idx, shift) = ((k - 1) / 32, (k - 1) % 32);
+
+        if idx >= self.len || (self.limbs[idx] >> shift) & 1 == 0 {
+            Ordering::Less
+        } else if self.limbs[idx] & ((1 << shift) - 1) != 0
+            || self.limbs[..idx].iter().any(|&limb| limb != 0)
+        {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Write the decimal digits to the start of `buf`, returns the length, 0
+    /// if zero.
+    fn write_decimal(mut self, buf: &mut [u8]) -> usize {
+        /// The max power of 10 within `u32`.
+        const CHUNK: u32 = 1_000_000_000;
+
+        let mut chunks = [0; LIMBS + 1];
+        let mut count = 0;
+
+        while !self.is_zero() {
+            chunks[count] = self.div_rem_small(CHUNK);
+            count += 1;
+        }
+
+        let Some(last) = count.checked_sub(1) else {
+            return 0;
+        };
+
+        let mut len = chunks[last].ilog10() as usize + 1;
+        write_decimal_u64(u64::from(chunks[last]), &mut buf[..len]);
+
+        for &chunk in chunks[..last].iter().rev() {
+            buf[len..len + 9].fill(b'0');
+            write_decimal_u64(u64::from(chunk), &mut buf[len..len + 9]);
+            len += 9;
+        }
+
+        len
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::string::{number::SignPlus, StringExtT};
+
+    #[test]
+    fn test_decimals() {
+        let fixed = |num: f64, decimals| FloatStr::new(num, Precision::Decimals(decimals));
+
+        assert_eq!(fixed(0.0, 2).to_string_ext(), "0.00");
+        assert_eq!(fixed(-0.0, 0).to_string_ext(), "-0");
+        assert_eq!(fixed(1234.5678, 2).to_string_ext(), "1234.57");
+        assert_eq!(fixed(1234.5678, 0).to_string_ext(), "1235");
+        assert_eq!(fixed(1e-7, 3).to_string_ext(), "0.000");
+        assert_eq!(fixed(1e-7, 8).to_string_ext(), "0.00000010");
+        assert_eq!(fixed(-0.001, 2).to_string_ext(), "-0.00");
+        assert_eq!(fixed(99.96, 1).to_string_ext(), "100.0");
+        assert_eq!(fixed(0.6, 0).to_string_ext(), "1");
+        assert_eq!(fixed(1e21, 1).to_string_ext(), "1000000000000000000000.0");
+
+        // Round half to even, on the exact value.
+        assert_eq!(fixed(0.5, 0).to_string_ext(), "0");
+        assert_eq!(fixed(1.5, 0).to_string_ext(), "2");
+        assert_eq!(fixed(2.5, 0).to_string_ext(), "2");
+        assert_eq!(fixed(0.125, 2).to_string_ext(), "0.12");
+        assert_eq!(fixed(0.375, 2).to_string_ext(), "0.38");
+        assert_eq!(fixed(1.005, 2).to_string_ext(), "1.00");
+        assert_eq!(fixed(2.675, 2).to_string_ext(), "2.67");
+
+        // Exact digits.
+        assert_eq!(
+            fixed(0.1, 30).to_string_ext(),
+            "0.100000000000000005551115123126"
+        );
+        assert_eq!(
+            fixed(f64::MAX, 0).to_string_ext(),
+            format!("{:.0}", f64::MAX)
+        );
+        assert_eq!(
+            fixed(f64::MIN_POSITIVE / 8.0, 1100).to_string_ext(),
+            format!("{:.1100}", f64::MIN_POSITIVE / 8.0)
+        );
+    }
+
+    #[test]
+    fn test_decimals_std() {
+        let values = [
+            0.1,
+            0.5,
+            0.05,
+            0.005,
+            123.456,
+            9.999,
+            1e-10,
+            1e15,
+            1e16,
+            1e22,
+            1e23,
+            1e300,
+            5e-324,
+            1.0 / 3.0,
+        ];
+
+        for value in values
+            .into_iter()
+            .chain((0..1000).map(|_| f64::from_bits(rand::random::<u64>())))
+            .chain((0..1000).map(|_| rand::random::<f64>() * 1e6))
+            .filter(|value| value.is_finite())
+        {
+            for decimals in [0, 1, 2, 3, 6, 17, 40] {
+                assert_eq!(
+                    FloatStr::new(value, Precision::Decimals(decimals)).to_string_ext(),
+                    format!("{value:.decimals$}"),
+                );
+                assert_eq!(
+                    FloatStr::new(value, Precision::Decimals(decimals))
+                        .notation(Notation::Scientific)
+                        .to_string_ext(),
+                    format!("{value:.decimals$e}"),
+                );
+                assert_eq!(
+                    FloatStr::new(value, Precision::Significant(decimals + 1))
+                        .notation(Notation::Scientific)
+                        .to_string_ext(),
+                    format!("{value:.decimals$e}"),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_significant() {
+        let significant = |num: f64, digits| FloatStr::new(num, Precision::Significant(digits));
+
+        assert_eq!(significant(0.0, 3).to_string_ext(), "0.00");
+        assert_eq!(significant(1234.5678, 0).to_string_ext(), "1000");
+        assert_eq!(significant(1234.5678, 3).to_string_ext(), "1230");
+        assert_eq!(significant(1234.5678, 6).to_string_ext(), "1234.57");
+        assert_eq!(significant(0.00012345, 3).to_string_ext(), "0.000123");
+        assert_eq!(significant(9.96, 2).to_string_ext(), "10");
+        assert_eq!(significant(1.5, 4).to_string_ext(), "1.500");
+        assert_eq!(significant(1e-7, 2).to_string_ext(), "0.00000010");
+        assert_eq!(significant(125.0, 2).to_string_ext(), "120");
+    }
+
+    #[test]
+    fn test_notation() {
+        let scientific =
+            |num: f64, precision| FloatStr::new(num, precision).notation(Notation::Scientific);
+        let engineering =
+            |num: f64, precision| FloatStr::new(num, precision).notation(Notation::Engineering);
+
+        assert_eq!(
+            scientific(0.0, Precision::Decimals(2)).to_string_ext(),
+            "0.00e0"
+        );
+        assert_eq!(
+            scientific(1e-7, Precision::Decimals(0)).to_string_ext(),
+            "1e-7"
+        );
+        assert_eq!(
+            scientific(9.96, Precision::Significant(2)).to_string_ext(),
+            "1.0e1"
+        );
+        assert_eq!(
+            scientific(-1234.5, Precision::Significant(3)).to_string_ext(),
+            "-1.23e3"
+        );
+
+        assert_eq!(
+            engineering(0.0, Precision::Decimals(1)).to_string_ext(),
+            "0.0e0"
+        );
+        assert_eq!(
+            engineering(1.0, Precision::Decimals(1)).to_string_ext(),
+            "1.0e0"
+        );
+        assert_eq!(
+            engineering(12345.0, Precision::Decimals(2)).to_string_ext(),
+            "12.34e3"
+        );
+        assert_eq!(
+            engineering(123456.0, Precision::Decimals(0)).to_string_ext(),
+            "123e3"
+        );
+        assert_eq!(
+            engineering(999.96, Precision::Decimals(1)).to_string_ext(),
+            "1.0e3"
+        );
+        assert_eq!(
+            engineering(0.000012345, Precision::Significant(2)).to_string_ext(),
+            "12e-6"
+        );
+        assert_eq!(
+            engineering(0.00012345, Precision::Significant(2)).to_string_ext(),
+            "120e-6"
+        );
+        assert_eq!(
+            engineering(0.00012345, Precision::Significant(4)).to_string_ext(),
+            "123.4e-6"
+        );
+        assert_eq!(
+            engineering(1e-7, Precision::Decimals(1)).to_string_ext(),
+            "100.0e-9"
+        );
+    }
+
+    #[test]
+    fn test_float_str() {
+        assert_eq!(
+            FloatStr::new(f64::NAN, Precision::Decimals(2)).to_string_ext(),
+            "NaN"
+        );
+        assert_eq!(
+            FloatStr::new(f64::NEG_INFINITY, Precision::Decimals(2)).to_string_ext(),
+            "-inf"
+        );
+
+        // From `NumStr`, keeping the sign.
+        let num = NumStr::new_default(2.5_f32).set_sign::<SignPlus>();
+        assert_eq!(
+            num.with_precision(Precision::Decimals(0)).to_string_ext(),
+            "+2"
+        );
+        assert_eq!(
+            num.set_resize_len::<4>()
+                .with_precision(Precision::Significant(3))
+                .to_string_ext(),
+            "+2.50"
+        );
+        assert_eq!(
+            FloatStr::new(0.1_f32, Precision::Decimals(10)).to_string_ext(),
+            "0.1000000015"
+        );
+    }
+}
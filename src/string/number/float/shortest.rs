@@ -0,0 +1,646 @@
+//! Shortest digits which round-trip, see [`Shortest`].
+//!
+//! Grisu3 (Florian Loitsch, 2010) is tried first, which gives up on about 0.5%
+//! of the inputs, then the exact Dragon4 (Steele & White, 1990) with the
+//! improvements by Burger & Dybvig (1996).
+//!
+//! The digits are the same as `format!("{}", ...)`: the shortest ones within
+//! the rounding interval, and the closest to the value among them, or the upper
+//! one on ties.
+
+use std::cmp::Ordering;
+
+use super::Big;
+use crate::string::BufT;
+
+/// Max significant digits of the shortest representation, and one more for
+/// the carry when rounding up.
+const MAX_DIGITS: usize = 18;
+
+/// Max length of the positional form, like `-0.` and 323 zeros for `5e-324`,
+/// or 309 digits and `.0` for `f64::MAX`, while subnormal numbers have less
+/// significant digits.
+pub(in crate::string::number) const MAX_LEN: usize = 3 + 323 + 17;
+
+/// Shortest significant digits of a finite non-negative float, the value is
+/// `0.DIGITS * 10^exp10`.
+pub(in crate::string::number) struct Shortest {
+    buf: [u8; MAX_DIGITS],
+    len: usize,
+    exp10: isize,
+}
+
+impl Shortest {
+    /// Write in positional notation, like `0.001`, `1.5` or `1000`, the same
+    /// as `format!("{}", ...)`.
+    pub(in crate::string::number) fn encode<BUF: BufT>(&self, string: &mut BUF) {
+        let digits = &self.buf[..self.len];
+
+        if digits.is_empty() {
+            string.push(b'0');
+            return;
+        }
+
+        match usize::try_from(self.exp10) {
+            Ok(int_len) if int_len >= digits.len() => {
+                string.extend_from_slice(digits);
+                string.resize(string.len() + int_len - digits.len(), b'0');
+            }
+            Ok(int_len) if int_len > 0 => {
+                string.extend_from_slice(&digits[..int_len]);
+                string.push(b'.');
+                string.extend_from_slice(&digits[int_len..]);
+            }
+            _ => {
+                string.extend_from_slice(b"0.");
+                string.resize(string.len() + self.exp10.unsigned_abs(), b'0');
+                string.extend_from_slice(digits);
+            }
+        }
+    }
+
+    fn new(decoded: Decoded) -> Self {
+        let mut this = Self {
+            buf: [0; MAX_DIGITS],
+            len: 0,
+            exp10: 0,
+        };
+
+        if decoded.mant == 0 {
+            return this;
+        }
+
+        let (len, exp10) =
+            grisu(&decoded, &mut this.buf).unwrap_or_else(|| dragon(&decoded, &mut this.buf));
+
+        this.len = len;
+        this.exp10 = exp10;
+        this
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty => $fraction_bits:expr, $min_exp:expr);+) => {
+        $(
+            impl From<$ty> for Shortest {
+                #[inline]
+                fn from(value: $ty) -> Self {
+                    debug_assert!(value.is_finite() && value.is_sign_positive());
+
+                    let bits = value.to_bits();
+                    let exp_bits = (bits >> $fraction_bits) as i16;
+                    let fraction = u64::from(bits & ((1 << $fraction_bits) - 1));
+
+                    Self::new(Decoded::new(fraction, exp_bits, $fraction_bits, $min_exp))
+                }
+            }
+        )+
+    };
+}
+
+impl_from!(f32 => 23, -149; f64 => 52, -1074);
+
+/// The value is `mant * 2^exp`, and any number within
+/// `(mant - minus) * 2^exp ..= (mant + plus) * 2^exp` rounds to it, bounds
+/// included if `inclusive`.
+struct Decoded {
+    mant: u64,
+    minus: u64,
+    plus: u64,
+    exp: i16,
+    inclusive: bool,
+}
+
+impl Decoded {
+    /// From the biased exponent bits and fraction bits of a finite float.
+    const fn new(fraction: u64, exp_bits: i16, fraction_bits: u32, min_exp: i16) -> Self {
+        if exp_bits == 0 {
+            // Subnormal, the neighbours are equally spaced.
+            return Self {
+                mant: fraction << 1,
+                minus: 1,
+                plus: 1,
+                exp: min_exp - 1,
+                inclusive: fraction % 2 == 0,
+            };
+        }
+
+        let mant = fraction | (1 << fraction_bits);
+        let exp = exp_bits - 1 + min_exp;
+
+        if fraction == 0 {
+            // The lower neighbour is closer, by half.
+            Self {
+                mant: mant << 2,
+                minus: 1,
+                plus: 2,
+                exp: exp - 2,
+                inclusive: true,
+            }
+        } else {
+            Self {
+                mant: mant << 1,
+                minus: 1,
+                plus: 1,
+                exp: exp - 1,
+                inclusive: mant % 2 == 0,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+/// Floating point number without sign, `f * 2^e`.
+struct Fp {
+    f: u64,
+    e: i16,
+}
+
+impl Fp {
+    /// Make the highest bit set.
+    const fn normalize(self) -> Self {
+        let shift = self.f.leading_zeros();
+
+        Self {
+            f: self.f << shift,
+            e: self.e - shift as i16,
+        }
+    }
+
+    /// Make the exponent `e`, which should not lose any bits.
+    const fn normalize_to(self, e: i16) -> Self {
+        Self {
+            f: self.f << (self.e - e),
+            e,
+        }
+    }
+
+    /// Multiply, with the lower half rounded.
+    const fn mul(self, other: Self) -> Self {
+        let product = self.f as u128 * other.f as u128 + (1 << 63);
+
+        Self {
+            f: (product >> 64) as u64,
+            e: self.e + other.e + 64,
+        }
+    }
+}
+
+/// Range of the exponent after scaled by the cached power of 10, so that the
+/// integral part fits in `u32`.
+const ALPHA: i16 = -60;
+const GAMMA: i16 = -32;
+
+/// Normalized `10^k` as `(f, e, k)`, `k` from -348 to 340 by 8.
+const CACHED_POW10: [(u64, i16, i16); 87] = [
+    (0xfa8fd5a0081c0288, -1220, -348),
+    (0xbaaee17fa23ebf76, -1193, -340),
+    (0x8b16fb203055ac76, -1166, -332),
+    (0xcf42894a5dce35ea, -1140, -324),
+    (0x9a6bb0aa55653b2d, -1113, -316),
+    (0xe61acf033d1a45df, -1087, -308),
+    (0xab70fe17c79ac6ca, -1060, -300),
+    (0xff77b1fcbebcdc4f, -1034, -292),
+    (0xbe5691ef416bd60c, -1007, -284),
+    (0x8dd01fad907ffc3c, -980, -276),
+    (0xd3515c2831559a83, -954, -268),
+    (0x9d71ac8fada6c9b5, -927, -260),
+    (0xea9c227723ee8bcb, -901, -252),
+    (0xaecc49914078536d, -874, -244),
+    (0x823c12795db6ce57, -847, -236),
+    (0xc21094364dfb5637, -821, -228),
+    (0x9096ea6f3848984f, -794, -220),
+    (0xd77485cb25823ac7, -768, -212),
+    (0xa086cfcd97bf97f4, -741, -204),
+    (0xef340a98172aace5, -715, -196),
+    (0xb23867fb2a35b28e, -688, -188),
+    (0x84c8d4dfd2c63f3b, -661, -180),
+    (0xc5dd44271ad3cdba, -635, -172),
+    (0x936b9fcebb25c996, -608, -164),
+    (0xdbac6c247d62a584, -582, -156),
+    (0xa3ab66580d5fdaf6, -555, -148),
+    (0xf3e2f893dec3f126, -529, -140),
+    (0xb5b5ada8aaff80b8, -502, -132),
+    (0x87625f056c7c4a8b, -475, -124),
+    (0xc9bcff6034c13053, -449, -116),
+    (0x964e858c91ba2655, -422, -108),
+    (0xdff9772470297ebd, -396, -100),
+    (0xa6dfbd9fb8e5b88f, -369, -92),
+    (0xf8a95fcf88747d94, -343, -84),
+    (0xb94470938fa89bcf, -316, -76),
+    (0x8a08f0f8bf0f156b, -289, -68),
+    (0xcdb02555653131b6, -263, -60),
+    (0x993fe2c6d07b7fac, -236, -52),
+    (0xe45c10c42a2b3b06, -210, -44),
+    (0xaa242499697392d3, -183, -36),
+    (0xfd87b5f28300ca0e, -157, -28),
+    (0xbce5086492111aeb, -130, -20),
+    (0x8cbccc096f5088cc, -103, -12),
+    (0xd1b71758e219652c, -77, -4),
+    (0x9c40000000000000, -50, 4),
+    (0xe8d4a51000000000, -24, 12),
+    (0xad78ebc5ac620000, 3, 20),
+    (0x813f3978f8940984, 30, 28),
+    (0xc097ce7bc90715b3, 56, 36),
+    (0x8f7e32ce7bea5c70, 83, 44),
+    (0xd5d238a4abe98068, 109, 52),
+    (0x9f4f2726179a2245, 136, 60),
+    (0xed63a231d4c4fb27, 162, 68),
+    (0xb0de65388cc8ada8, 189, 76),
+    (0x83c7088e1aab65db, 216, 84),
+    (0xc45d1df942711d9a, 242, 92),
+    (0x924d692ca61be758, 269, 100),
+    (0xda01ee641a708dea, 295, 108),
+    (0xa26da3999aef774a, 322, 116),
+    (0xf209787bb47d6b85, 348, 124),
+    (0xb454e4a179dd1877, 375, 132),
+    (0x865b86925b9bc5c2, 402, 140),
+    (0xc83553c5c8965d3d, 428, 148),
+    (0x952ab45cfa97a0b3, 455, 156),
+    (0xde469fbd99a05fe3, 481, 164),
+    (0xa59bc234db398c25, 508, 172),
+    (0xf6c69a72a3989f5c, 534, 180),
+    (0xb7dcbf5354e9bece, 561, 188),
+    (0x88fcf317f22241e2, 588, 196),
+    (0xcc20ce9bd35c78a5, 614, 204),
+    (0x98165af37b2153df, 641, 212),
+    (0xe2a0b5dc971f303a, 667, 220),
+    (0xa8d9d1535ce3b396, 694, 228),
+    (0xfb9b7cd9a4a7443c, 720, 236),
+    (0xbb764c4ca7a44410, 747, 244),
+    (0x8bab8eefb6409c1a, 774, 252),
+    (0xd01fef10a657842c, 800, 260),
+    (0x9b10a4e5e9913129, 827, 268),
+    (0xe7109bfba19c0c9d, 853, 276),
+    (0xac2820d9623bf429, 880, 284),
+    (0x80444b5e7aa7cf85, 907, 292),
+    (0xbf21e44003acdd2d, 933, 300),
+    (0x8e679c2f5e44ff8f, 960, 308),
+    (0xd433179d9c8cb841, 986, 316),
+    (0x9e19db92b4e31ba9, 1013, 324),
+    (0xeb96bf6ebadf77d9, 1039, 332),
+    (0xaf87023b9bf0ee6b, 1066, 340),
+];
+
+/// Returns `k` and the cached `10^-k` so that its product with `2^e` has the
+/// exponent within `ALPHA ..= GAMMA`.
+fn cached_power(e: i16) -> (i16, Fp) {
+    let min_e = ALPHA - e - 64;
+
+    // The exponents of the entries grow by 26 or 27, start from an
+    // underestimated index.
+    let mut idx = ((i32::from(min_e) - i32::from(CACHED_POW10[0].1)).max(0) / 27) as usize;
+    while CACHED_POW10[idx].1 < min_e {
+        idx += 1;
+    }
+
+    let (f, cached_e, k) = CACHED_POW10[idx];
+    debug_assert!(cached_e <= min_e + (GAMMA - ALPHA));
+
+    (k, Fp { f, e: cached_e })
+}
+
+/// Returns the largest `kappa` and `10^kappa` no more than `num`.
+const fn max_pow10_no_more_than(num: u32) -> (u32, u32) {
+    match num.checked_ilog10() {
+        Some(kappa) => (kappa, 10u32.pow(kappa)),
+        None => (0, 1),
+    }
+}
+
+/// Grisu3, returns the length of digits and `exp10`, or `None` if not sure
+/// about the result.
+fn grisu(d: &Decoded, buf: &mut [u8; MAX_DIGITS]) -> Option<(usize, isize)> {
+    // Normalized with the shared exponent.
+    let plus = Fp {
+        f: d.mant + d.plus,
+        e: d.exp,
+    }
+    .normalize();
+    let minus = Fp {
+        f: d.mant - d.minus,
+        e: d.exp,
+    }
+    .normalize_to(plus.e);
+    let v = Fp {
+        f: d.mant,
+        e: d.exp,
+    }
+    .normalize_to(plus.e);
+
+    // Scaled by `10^-k`, with the error of 1 ulp at most.
+    let (minus_k, cached) = cached_power(plus.e);
+    let plus = plus.mul(cached);
+    let minus = minus.mul(cached);
+    let v = v.mul(cached);
+
+    // The liberal interval, see `round_and_weed` for the conservative one.
+    let plus1 = plus.f + 1;
+    let minus1 = minus.f - 1;
+    let e = -plus.e as u32;
+
+    let plus1_int = (plus1 >> e) as u32;
+    let plus1_frac = plus1 & ((1 << e) - 1);
+
+    let (max_kappa, max_ten_kappa) = max_pow10_no_more_than(plus1_int);
+    let exp10 = max_kappa as isize - minus_k as isize + 1;
+
+    let delta1 = plus1 - minus1;
+    let delta1_frac = delta1 & ((1 << e) - 1);
+
+    let mut len = 0;
+
+    // The integral part, until the remainder is within the interval.
+    let mut ten_kappa = max_ten_kappa;
+    let mut remainder = plus1_int;
+    loop {
+        let q = remainder / ten_kappa;
+        let r = remainder % ten_kappa;
+        buf[len] = b'0' + q as u8;
+        len += 1;
+
+        let plus1_rem = (u64::from(r) << e) + plus1_frac;
+        if plus1_rem < delta1 {
+            return round_and_weed(
+                &mut buf[..len],
+                plus1_rem,
+                delta1,
+                plus1 - v.f,
+                u64::from(ten_kappa) << e,
+                1,
+            )
+            .then_some((len, exp10));
+        }
+
+        if len > max_kappa as usize {
+            break;
+        }
+
+        ten_kappa /= 10;
+        remainder = r;
+    }
+
+    // The fraction part, by multiplying instead of dividing.
+    let mut remainder = plus1_frac;
+    let mut threshold = delta1_frac;
+    let mut ulp = 1;
+    loop {
+        remainder *= 10;
+        threshold *= 10;
+        ulp *= 10;
+
+        let q = remainder >> e;
+        let r = remainder & ((1 << e) - 1);
+        buf[len] = b'0' + q as u8;
+        len += 1;
+
+        if r < threshold {
+            return round_and_weed(
+                &mut buf[..len],
+                r,
+                threshold,
+                (plus1 - v.f) * ulp,
+                1 << e,
+                ulp,
+            )
+            .then_some((len, exp10));
+        }
+
+        remainder = r;
+    }
+}
+
+/// Decrease the last digit until the closest to the value, and check that the
+/// result is within the conservative interval.
+///
+/// All arguments are scaled by the same implicit factor:
+///
+/// - `remainder`: `plus1 % 10^kappa`
+/// - `threshold`: `plus1 - minus1`
+/// - `plus1v`: `plus1 - v`
+/// - `ten_kappa`: `10^kappa`
+/// - `ulp`: the unit of the last place
+fn round_and_weed(
+    buf: &mut [u8],
+    remainder: u64,
+    threshold: u64,
+    plus1v: u64,
+    ten_kappa: u64,
+    ulp: u64,
+) -> bool {
+    // `plus1 - (v - 1 ulp)` and `plus1 - (v + 1 ulp)`.
+    let plus1v_down = plus1v + ulp;
+    let plus1v_up = plus1v - ulp;
+
+    // `plus1 - w`, where `w` is the current digits.
+    let mut plus1w = remainder;
+
+    // Whether the next `w` is closer to `target`.
+    let closer = |plus1w: u64, target: u64| {
+        plus1w < target
+            && threshold - plus1w >= ten_kappa
+            && (plus1w + ten_kappa < target || target - plus1w >= plus1w + ten_kappa - target)
+    };
+
+    let Some(last) = buf.last_mut() else {
+        return false;
+    };
+
+    while closer(plus1w, plus1v_up) {
+        *last -= 1;
+        plus1w += ten_kappa;
+    }
+
+    // Not sure which one is the closest to `v`.
+    if closer(plus1w, plus1v_down) {
+        return false;
+    }
+
+    // Within `minus0 ..= plus0`, which are `2 ulp` away from `minus1` and `plus1`.
+    2 * ulp <= plus1w && plus1w <= threshold - 4 * ulp
+}
+
+/// Dragon4, returns the length of digits and `exp10`.
+fn dragon(d: &Decoded, buf: &mut [u8; MAX_DIGITS]) -> (usize, isize) {
+    // `a.compare(&b) < rounding` is `a <= b` if inclusive, or `a < b`.
+    let rounding = if d.inclusive {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    };
+
+    // `10^(k - 1) < high <= 10^(k + 1)`, `log10(2)` is about
+    // `1292913986 / 2^32`.
+    let bits = 64 - i64::from((d.mant + d.plus - 1).leading_zeros());
+    let mut k = (((bits + i64::from(d.exp)) * 1_292_913_986) >> 32) as isize;
+
+    // `v = mant / scale`, `low = (mant - minus) / scale` and
+    // `high = (mant + plus) / scale`.
+    let mut mant = Big::from_u64(d.mant);
+    let mut minus = Big::from_u64(d.minus);
+    let mut plus = Big::from_u64(d.plus);
+    let mut scale = Big::from_u64(1);
+
+    if d.exp < 0 {
+        scale.shl(d.exp.unsigned_abs() as usize);
+    } else {
+        mant.shl(d.exp as usize);
+        minus.shl(d.exp as usize);
+        plus.shl(d.exp as usize);
+    }
+
+    // Divided by `10^k`.
+    if k >= 0 {
+        scale.mul_pow10(k.unsigned_abs());
+    } else {
+        mant.mul_pow10(k.unsigned_abs());
+        minus.mul_pow10(k.unsigned_abs());
+        plus.mul_pow10(k.unsigned_abs());
+    }
+
+    // Make `scale < mant + plus <= scale * 10`.
+    if scale.compare(&mant.added(&plus)) < rounding {
+        k += 1;
+    } else {
+        mant.mul_small(10);
+        minus.mul_small(10);
+        plus.mul_small(10);
+    }
+
+    let mut scales = [scale; 4];
+    for (bits, scale) in scales.iter_mut().enumerate() {
+        scale.shl(bits);
+    }
+
+    let mut len = 0;
+    let (down, up) = loop {
+        // `mant < scale * 10`.
+        let mut digit = 0;
+        for (bits, scale) in scales.iter().enumerate().rev() {
+            if mant.compare(scale) != Ordering::Less {
+                mant.sub(scale);
+                digit += 1 << bits;
+            }
+        }
+
+        buf[len] = b'0' + digit;
+        len += 1;
+
+        // Round down if within `low`, up if within `high`.
+        let down = mant.compare(&minus) < rounding;
+        let up = scale.compare(&mant.added(&plus)) < rounding;
+        if down || up {
+            break (down, up);
+        }
+
+        mant.mul_small(10);
+        minus.mul_small(10);
+        plus.mul_small(10);
+    };
+
+    // The closest one, or the upper one on ties.
+    if up
+        && (!down || {
+            mant.shl(1);
+            mant.compare(&scale) != Ordering::Less
+        })
+    {
+        match buf[..len].iter().rposition(|&digit| digit != b'9') {
+            Some(idx) => {
+                buf[idx] += 1;
+                buf[idx + 1..len].fill(b'0');
+            }
+            None => {
+                buf[0] = b'1';
+                buf[1..len].fill(b'0');
+                buf[len] = b'0';
+                len += 1;
+                k += 1;
+            }
+        }
+    }
+
+    (len, k)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shortest() {
+        fn shortest(value: impl Into<Shortest>) -> String {
+            let mut string = Vec::new();
+            value.into().encode(&mut string);
+            String::from_utf8(string).unwrap()
+        }
+
+        assert_eq!(shortest(0.0), "0");
+        assert_eq!(shortest(1.0), "1");
+        assert_eq!(shortest(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(shortest(1e-7), "0.0000001");
+        assert_eq!(shortest(1.5e16), "15000000000000000");
+        assert_eq!(shortest(0.1_f32), "0.1");
+        // `1763605585676660.25`, the upper one on ties.
+        assert_eq!(
+            shortest(f64::from_bits(0x4319_0ff5_b1d5_25d1)),
+            "1763605585676660.3"
+        );
+
+        for value in [
+            5e-324,
+            1e21,
+            1e23,
+            123.456,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::EPSILON,
+        ]
+        .into_iter()
+        .chain((0..10000).map(|_| f64::from_bits(rand::random::<u64>())))
+        .chain((0..10000).map(|_| rand::random::<f64>() * 1e6))
+        .filter(|value| value.is_finite())
+        .map(f64::abs)
+        {
+            assert_eq!(shortest(value), format!("{value}"));
+        }
+
+        for value in [f32::MAX, f32::MIN_POSITIVE, 1e-45, 16777217.0]
+            .into_iter()
+            .chain((0..10000).map(|_| f32::from_bits(rand::random::<u32>())))
+            .filter(|value| value.is_finite())
+            .map(f32::abs)
+        {
+            assert_eq!(shortest(value), format!("{value}"));
+        }
+    }
+
+    #[test]
+    fn test_dragon() {
+        // Grisu gives up rarely, test the fallback on its own.
+        fn dragon_only(value: f64) -> String {
+            let bits = value.to_bits();
+            let decoded = Decoded::new(bits & ((1 << 52) - 1), (bits >> 52) as i16, 52, -1074);
+
+            let mut shortest = Shortest {
+                buf: [0; MAX_DIGITS],
+                len: 0,
+                exp10: 0,
+            };
+            (shortest.len, shortest.exp10) = dragon(&decoded, &mut shortest.buf);
+
+            let mut string = Vec::new();
+            shortest.encode(&mut string);
+            String::from_utf8(string).unwrap()
+        }
+
+        for value in [5e-324, 1e23, f64::MAX, f64::MIN_POSITIVE, 0.3, 9.5]
+            .into_iter()
+            .chain((0..10000).map(|_| f64::from_bits(rand::random::<u64>())))
+            .filter(|value| value.is_finite() && *value != 0.0)
+            .map(f64::abs)
+        {
+            assert_eq!(dragon_only(value), format!("{value}"));
+        }
+    }
+}
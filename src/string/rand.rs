@@ -2,7 +2,7 @@
 
 use rand::{distributions::Slice, Rng};
 
-use super::{NumStr, StringExtT, StringT};
+use super::{BufT, NumStr, StringExtT, StringT};
 use crate::random::fast_random;
 
 #[macro_export]
@@ -69,7 +69,7 @@ pub struct RandHexStr<const L: usize = 16, const RP: usize = 1, const LP: usize
 
 impl<const L: usize, const RP: usize, const LP: usize> StringT for RandHexStr<L, RP, LP> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         match L {
             1..=16 => {
                 for _ in 0..RP {
@@ -94,60 +94,19 @@ impl<const L: usize, const RP: usize, const LP: usize> StringT for RandHexStr<L,
                 #[cfg(not(any(debug_assertions, test)))]
                 // For RELEASE mode, avoid panic but still generate random string like general
                 // RandStr does.
-                string.extend(
-                    rand::thread_rng()
-                        .sample_iter(&Slice::new(b"0123456789abcdef").unwrap())
-                        .take(L * RP + LP),
-                );
+                rand::thread_rng()
+                    .sample_iter(&Slice::new(b"0123456789abcdef").unwrap())
+                    .take(L * RP + LP)
+                    .for_each(|&byte| string.push(byte));
             }
         }
     }
 
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-        self.encode_to_buf(string);
-        string.extend(separator.as_bytes());
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        match L {
-            1..=16 => {
-                for _ in 0..RP {
-                    NumStr::hex_default(fast_random())
-                        .set_resize_len::<L>()
-                        .encode_to_bytes_buf(string);
-                }
-
-                if LP > 0 {
-                    debug_assert!(LP <= 16, "LP should be 0..=16");
-
-                    NumStr::hex_default(fast_random())
-                        .set_resize_len::<LP>()
-                        .encode_to_bytes_buf(string);
-                }
-            }
-            0 => {}
-            _ => {
-                #[cfg(any(debug_assertions, test))]
-                unreachable!("L should be 0..=16");
-
-                #[cfg(not(any(debug_assertions, test)))]
-                // For RELEASE mode, avoid panic but still generate random string like general
-                // RandStr does.
-                string.extend(
-                    rand::thread_rng()
-                        .sample_iter(&Slice::new(b"0123456789abcdef").unwrap())
-                        .take(L * RP + LP),
-                );
-            }
-        }
-    }
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let len = if L == 0 { 0 } else { L * RP + LP };
 
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        self.encode_to_bytes_buf(string);
-        string.extend(separator.as_bytes());
+        (len, Some(len))
     }
 }
 
@@ -228,59 +187,22 @@ pub struct RandStr<'r, const L: usize = 32>(&'r [u8]);
 
 impl<const L: usize> StringT for RandStr<'_, L> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
-        if self.0.is_empty() {
-            return;
-        }
-
-        string.extend(
-            rand::thread_rng()
-                .sample_iter(Slice::new(self.0).unwrap())
-                .take(L),
-        );
-    }
-
-    #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
         if self.0.is_empty() {
             return;
         }
 
-        string.extend(
-            rand::thread_rng()
-                .sample_iter(Slice::new(self.0).unwrap())
-                .take(L),
-        );
-
-        string.extend(separator.as_bytes());
-    }
-
-    #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        if self.0.is_empty() {
-            return;
-        }
-
-        string.extend(
-            rand::thread_rng()
-                .sample_iter(Slice::new(self.0).unwrap())
-                .take(L),
-        );
+        rand::thread_rng()
+            .sample_iter(Slice::new(self.0).unwrap())
+            .take(L)
+            .for_each(|&byte| string.push(byte));
     }
 
     #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        if self.0.is_empty() {
-            return;
-        }
-
-        string.extend(
-            rand::thread_rng()
-                .sample_iter(Slice::new(self.0).unwrap())
-                .take(L),
-        );
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.0.is_empty() { 0 } else { L };
 
-        string.extend(separator.as_bytes());
+        (len, Some(len))
     }
 }
 
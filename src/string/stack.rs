@@ -0,0 +1,479 @@
+//! Stack-allocated string with fixed capacity.
+//!
+//! Useful for building short strings like cache keys or metric labels, where
+//! the heap allocation dominates.
+
+use std::{fmt, hash, ops::Deref};
+
+use super::{BufT, PushAnyT, Separator, StringExtT, StringT};
+
+#[derive(Clone, Copy)]
+/// String with fixed capacity `N` (in bytes), stored inline.
+///
+/// [`PushAnyT`] is implemented with the *truncating* policy: when the value
+/// does not fit, the longest prefix that fits (at a char boundary) is kept,
+/// the string is marked as truncated and all subsequent pushes are ignored.
+/// Values modified in place while encoded (like numbers or escaped strings)
+/// are dropped as a whole instead, since the bytes beyond the capacity are
+/// never stored: no heap allocation at all.
+///
+/// For the *fallible* policy, see [`StackString::try_push_any`].
+///
+/// # Example
+///
+/// ```rust
+/// # use macro_toolset::{str_concat, string::{NumStr, StackString}};
+/// let key = str_concat!(stack = 32; "user:", 42u64, ":", NumStr::hex_default(255u8));
+/// assert_eq!(key, "user:42:ff");
+/// assert!(!key.is_truncated());
+///
+/// let mut label = StackString::<4>::new();
+/// assert!(label.try_push_any("hello").is_err());
+/// assert_eq!(label, "");
+/// ```
+pub struct StackString<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The error returned by [`StackString::try_push_any`] when there's no enough
+/// capacity left.
+pub struct CapacityError;
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+impl std::error::Error for CapacityError {}
+
+impl<const N: usize> StackString<N> {
+    #[inline]
+    /// Create a new empty string.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    #[inline]
+    /// Returns the capacity (in bytes) of the string, i.e. `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    /// Returns the remaining capacity (in bytes) of the string.
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    #[inline]
+    /// Returns `true` if any pushed value has been truncated.
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    #[inline]
+    /// Extracts a string slice containing the entire string.
+    pub fn as_str(&self) -> &str {
+        // safe because of the `StringT` trait, and we only truncate at char
+        // boundaries.
+        #[allow(unsafe_code)]
+        unsafe {
+            std::str::from_utf8_unchecked(&self.buf[..self.len])
+        }
+    }
+
+    #[inline]
+    /// Clear the string, including the truncated mark.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+    }
+
+    #[inline]
+    /// Push any string-like type to the string, or return [`CapacityError`]
+    /// and leave the string unchanged if it does not fit.
+    pub fn try_push_any<V>(&mut self, value: V) -> Result<(), CapacityError>
+    where
+        V: StringT,
+    {
+        let mut writer = Writer::new(self);
+
+        value.encode_to_buf(&mut writer);
+
+        writer.finish(false)
+    }
+
+    #[inline]
+    /// Push any string-like type to the string with a separator, or return
+    /// [`CapacityError`] and leave the string unchanged if it does not fit.
+    pub fn try_push_any_with_separator<V>(
+        &mut self,
+        value: V,
+        sep: &str,
+    ) -> Result<(), CapacityError>
+    where
+        V: StringT,
+    {
+        let mut writer = Writer::new(self);
+
+        value.encode_to_buf_with_separator(&mut writer, &mut Separator::new(sep));
+
+        writer.finish(false)
+    }
+}
+
+impl<const N: usize> PushAnyT for StackString<N> {
+    #[inline]
+    fn push_any<V>(&mut self, value: V)
+    where
+        V: StringT,
+    {
+        if self.truncated {
+            return;
+        }
+
+        let mut writer = Writer::new(self);
+
+        value.encode_to_buf(&mut writer);
+
+        let _ = writer.finish(true);
+    }
+
+    #[inline]
+    fn push_any_with_separator<V>(&mut self, value: V, sep: &str)
+    where
+        V: StringT,
+    {
+        if self.truncated {
+            return;
+        }
+
+        let mut writer = Writer::new(self);
+
+        value.encode_to_buf_with_separator(&mut writer, &mut Separator::new(sep));
+
+        let _ = writer.finish(true);
+    }
+}
+
+impl<const N: usize> Default for StackString<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for StackString<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for StackString<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for StackString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for StackString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> PartialEq for StackString<N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for StackString<N> {}
+
+impl<const N: usize> PartialEq<str> for StackString<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for StackString<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> hash::Hash for StackString<N> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<const N: usize> StringT for StackString<N> {
+    #[inline]
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        (&self).encode_to_buf(string);
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<const N: usize> StringExtT for StackString<N> {}
+
+impl<const N: usize> StringT for &StackString<N> {
+    #[inline]
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        string.extend_from_slice(self.as_bytes());
+    }
+
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<const N: usize> StringExtT for &StackString<N> {}
+
+/// The [`BufT`] writing to [`StackString`].
+///
+/// The bytes beyond the capacity are discarded but still counted in
+/// [`len`](BufT::len), and the overflow policy is applied in
+/// [`Writer::finish`], no heap allocation at all.
+///
+/// Encoders may write more than needed first and modify the bytes written in
+/// place, which is not possible once some are discarded: they will see
+/// [`as_mut_slice`](BufT::as_mut_slice) shorter than `len` and skip it, so
+/// the whole value is dropped then.
+struct Writer<'s, const N: usize> {
+    string: &'s mut StackString<N>,
+    original_len: usize,
+    /// Including the discarded bytes.
+    len: usize,
+    /// Whether the bytes written were accessed when some were discarded.
+    tainted: bool,
+}
+
+impl<'s, const N: usize> Writer<'s, N> {
+    #[inline]
+    fn new(string: &'s mut StackString<N>) -> Self {
+        Self {
+            original_len: string.len,
+            len: string.len,
+            string,
+            tainted: false,
+        }
+    }
+
+    /// Apply the overflow policy.
+    fn finish(self, truncate: bool) -> Result<(), CapacityError> {
+        if self.len <= N && !self.tainted {
+            // May have overflowed, but shrunk back by the encoder itself.
+            self.string.len = self.len;
+
+            return Ok(());
+        }
+
+        if truncate && !self.tainted {
+            let len = match std::str::from_utf8(&self.string.buf) {
+                Ok(_) => N,
+                Err(e) => e.valid_up_to(),
+            };
+
+            self.string.len = len;
+        } else {
+            self.string.len = self.original_len;
+        }
+
+        if truncate {
+            self.string.truncated = true;
+        }
+
+        Err(CapacityError)
+    }
+}
+
+impl<const N: usize> BufT for Writer<'_, N> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
+
+    #[inline]
+    fn push(&mut self, byte: u8) {
+        self.extend_from_slice(&[byte]);
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        if let Some(buf) = self.string.buf.get_mut(self.len..) {
+            let kept = bytes.len().min(buf.len());
+            buf[..kept].copy_from_slice(&bytes[..kept]);
+        }
+
+        self.len += bytes.len();
+    }
+
+    #[inline]
+    fn resize(&mut self, new_len: usize, value: u8) {
+        if let Some(buf) = self.string.buf.get_mut(self.len..new_len.min(N)) {
+            buf.fill(value);
+        }
+
+        self.len = new_len;
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        if self.len > N {
+            self.tainted = true;
+        }
+
+        &mut self.string.buf[..self.len.min(N)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::str_concat;
+
+    #[test]
+    fn test_push_any() {
+        let mut string = StackString::<16>::new();
+
+        string.push_any("hello");
+        string.push_any(',');
+        string.push_any(123u16);
+        string.push_any(-4i8);
+        assert_eq!(string, "hello,123-4");
+        assert_eq!(string.remaining_capacity(), 5);
+        assert!(!string.is_truncated());
+
+        string.clear();
+        string.push_any_with_separator(("a", "b", 1u8), "-");
+        assert_eq!(string, "a-b-1");
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut string = StackString::<6>::new();
+        string.push_any("abcd");
+        string.push_any("😀");
+        assert_eq!(string, "abcd");
+        assert!(string.is_truncated());
+
+        // Ignored once truncated.
+        string.push_any("e");
+        assert_eq!(string, "abcd");
+
+        // Numbers are encoded in place, the whole value is dropped instead.
+        let mut string = StackString::<4>::new();
+        string.push_any(1234567u32);
+        assert_eq!(string, "");
+        assert!(string.is_truncated());
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "feat-string-ext-html",
+        feature = "feat-string-ext-urlencoding"
+    ))]
+    fn test_overflow_in_place() {
+        use crate::string::{html::Escape, urlencoding::Encode};
+
+        // Fits after escaped.
+        let mut string = StackString::<16>::new();
+        string.push_any(Escape::new("<a&b>"));
+        assert_eq!(string, "&lt;a&amp;b&gt;");
+        assert!(!string.is_truncated());
+
+        // The inner value alone fits, but not after escaped.
+        let mut string = StackString::<8>::new();
+        string.push_any("x");
+        string.push_any(Escape::new("<a&b>"));
+        assert_eq!(string, "x");
+        assert!(string.is_truncated());
+
+        // The inner value is longer than the capacity.
+        let mut string = StackString::<8>::new();
+        assert_eq!(
+            string.try_push_any(Encode::new("a b c d e f g h")),
+            Err(CapacityError)
+        );
+        assert_eq!(string, "");
+        assert!(!string.is_truncated());
+        // The whole value, i.e. the tuple, is dropped.
+        string.push_any(("x", Encode::new("a b c d e f g h")));
+        assert_eq!(string, "");
+        assert!(string.is_truncated());
+
+        // Never modified in place once the capacity is exceeded, no unescaped
+        // bytes are kept.
+        let mut string = StackString::<8>::new();
+        string.push_any(Escape::new(("<<<<", "<<<<", "<<<<")));
+        assert_eq!(string, "");
+        assert!(string.is_truncated());
+    }
+
+    #[test]
+    fn test_try_push_any() {
+        let mut string = StackString::<8>::new();
+        assert_eq!(string.try_push_any("abc"), Ok(()));
+        assert_eq!(string.try_push_any(123456u32), Err(CapacityError));
+        assert_eq!(string, "abc");
+        assert_eq!(string.try_push_any(12345u32), Ok(()));
+        assert_eq!(string, "abc12345");
+        assert!(!string.is_truncated());
+
+        let mut string = StackString::<3>::new();
+        assert_eq!(
+            string.try_push_any_with_separator(("a", "b", "c"), ","),
+            Err(CapacityError)
+        );
+        assert_eq!(string.try_push_any_with_separator(("a", "b"), ","), Ok(()));
+        assert_eq!(string, "a,b");
+    }
+
+    #[test]
+    fn test_nested() {
+        let key = str_concat!(stack = 8; "a", 1u8);
+        assert_eq!(str_concat!(key, ":", &key), "a1:a1");
+        assert_eq!(key.len_hint(), (2, Some(2)));
+        assert_eq!(&*key, "a1");
+
+        let truncated = str_concat!(stack = 2; "abc");
+        assert_eq!(truncated, "ab");
+        assert!(truncated.is_truncated());
+    }
+}
@@ -1,11 +1,25 @@
 //! URL Encoded string
 
-use super::{StringExtT, StringT};
-use crate::wrapper;
+pub mod query;
+pub mod set;
+pub mod url;
+
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+use self::set::{EncodeSetT, Unreserved};
+pub use self::{
+    query::{parse_query, NoneValue, QueryOrder, QueryPairs, QueryString},
+    url::UrlBuilder,
+};
+use super::{BufT, StringExtT, StringT, Uncommitted};
 
 #[macro_export]
 /// See [`Encode`] or [`Decode`] for more information.
 ///
+/// - `E:` / `D:`: percent-encoding, space is encoded as `%20`.
+/// - `F:` / `FD:`: `application/x-www-form-urlencoded`, space is encoded as
+///   `+` and `+` is decoded as space.
+///
 /// # Example
 ///
 /// ```
@@ -14,165 +28,501 @@ use crate::wrapper;
 /// assert_eq!(data, "%E4%BD%A0%E5%A5%BD%2C%20%E4%B8%96%E7%95%8C");
 /// let data = urlencoding_str!(D: "%E4%BD%A0%E5%A5%BD%2C%20%E4%B8%96%E7%95%8C").to_string_ext();
 /// assert_eq!(data, "你好, 世界");
+/// let data = urlencoding_str!(F: "a b+c").to_string_ext();
+/// assert_eq!(data, "a+b%2Bc");
+/// let data = urlencoding_str!(FD: "a+b%2Bc").to_string_ext();
+/// assert_eq!(data, "a b+c");
 /// ```
 macro_rules! urlencoding_str {
     (E: $data:expr) => {
-        $crate::string::urlencoding::Encode { inner: $data }
+        $crate::string::urlencoding::Encode::new($data)
     };
     (D: $data:expr) => {
-        $crate::string::urlencoding::Decode { inner: $data }
+        $crate::string::urlencoding::Decode::new($data)
+    };
+    (F: $data:expr) => {
+        $crate::string::urlencoding::Encode::new($data).set_form::<true>()
+    };
+    (FD: $data:expr) => {
+        $crate::string::urlencoding::Decode::new($data).set_form::<true>()
     };
 }
 
-wrapper! {
-    #[derive(Debug)]
-    /// `string` which is to be encoded.
-    pub Encode<T>(pub T)
+/// Implement what [`wrapper!`](crate::wrapper) generates, for wrapper types
+/// with extra generic parameters.
+macro_rules! impl_wrapper {
+    ([$($generics:tt)*] $name:ty => $inner:ty) => {
+        impl<$($generics)*> std::borrow::Borrow<$inner> for $name {
+            fn borrow(&self) -> &$inner {
+                &self.inner
+            }
+        }
+
+        impl<$($generics)*> std::ops::Deref for $name {
+            type Target = $inner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.inner
+            }
+        }
+
+        impl<$($generics)*> std::ops::DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.inner
+            }
+        }
+
+        impl<$($generics)*> AsRef<$inner> for $name {
+            fn as_ref(&self) -> &$inner {
+                &self.inner
+            }
+        }
+    };
 }
 
-impl<T> StringT for Encode<T>
-where
-    T: StringT,
-{
+#[derive(Debug, Clone, Copy)]
+/// `string` which is to be encoded.
+///
+/// # Generic
+///
+/// - F: `application/x-www-form-urlencoded` mode, space is encoded as `+`
+///   instead of `%20` (and `+` is always encoded), default false.
+/// - S: The [encoding set](set), which bytes are kept as is, default
+///   [`Unreserved`].
+///
+/// Create with [`Encode::new`] (or `From`), then
+/// [`set_form`](Encode::set_form) and
+/// [`set_encode_set`](Encode::set_encode_set).
+#[repr(transparent)]
+pub struct Encode<T, const F: bool = false, S = Unreserved> {
+    /// Inner value
+    pub inner: T,
+
+    encode_set: PhantomData<S>,
+}
+
+impl<T> Encode<T> {
     #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
-        let mut buf = Vec::with_capacity(64);
-        self.inner.encode_to_buf(&mut buf);
-
-        string.reserve_exact(buf.len() | 15);
-        buf.into_iter().for_each(|byte| {
-            if matches!(byte, b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' |  b'-' | b'.' | b'_' | b'~')
-            {
-                string.push(byte);
-            } else {
-                string.extend([b'%', to_hex_digit(byte >> 4), to_hex_digit(byte & 15)]);
-            }
-        });
+    /// Create a new [`Encode`], only unreserved characters are kept and space
+    /// is encoded as `%20`.
+    pub const fn new(inner: T) -> Self {
+        Self {
+            inner,
+            encode_set: PhantomData,
+        }
     }
+}
 
+impl<T, const F: bool, S> Encode<T, F, S> {
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-        self.encode_to_buf(string);
-        string.extend(separator.as_bytes());
+    /// Set whether to encode space as `+` (form mode).
+    pub fn set_form<const NF: bool>(self) -> Encode<T, NF, S> {
+        Encode {
+            inner: self.inner,
+            encode_set: PhantomData,
+        }
     }
 
     #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        let mut buf = Vec::with_capacity(64);
-        self.inner.encode_to_buf(&mut buf);
-
-        string.reserve(buf.len() | 15);
-        buf.into_iter().for_each(|byte| {
-            if matches!(byte, b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' |  b'-' | b'.' | b'_' | b'~')
-            {
-                string.extend(Some(byte));
-            } else {
-                string.extend([b'%', to_hex_digit(byte >> 4), to_hex_digit(byte & 15)]);
-            }
-        });
+    /// Set the [encoding set](set), like [`set::Path`] or user-defined ones.
+    pub fn set_encode_set<NS: EncodeSetT>(self) -> Encode<T, F, NS> {
+        Encode {
+            inner: self.inner,
+            encode_set: PhantomData,
+        }
     }
+}
 
+impl<T> From<T> for Encode<T> {
     #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        self.encode_to_bytes_buf(string);
-        string.extend(separator.as_bytes());
+    fn from(inner: T) -> Self {
+        Self::new(inner)
     }
 }
 
-impl<T> StringExtT for Encode<T> where T: StringT {}
+impl_wrapper!([T, const F: bool, S] Encode<T, F, S> => T);
 
-#[inline(always)]
-const fn to_hex_digit(digit: u8) -> u8 {
-    match digit {
-        0..=9 => b'0' + digit,
-        10..=255 => b'A' - 10 + digit,
+impl<T, const F: bool, S> StringT for Encode<T, F, S>
+where
+    T: StringT,
+    S: EncodeSetT,
+{
+    #[inline]
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        let start = string.len();
+
+        self.inner.encode_to_buf(&mut Uncommitted(&mut *string));
+
+        encode_in_place::<B, F, S>(string, start);
     }
-}
 
-wrapper! {
-    #[derive(Debug)]
-    /// `string` which is to be decoded.
-    pub Decode<T>(pub T)
+    #[inline]
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.len_hint();
+
+        // Each byte will be encoded to at most 3 bytes.
+        (lower, upper.and_then(|upper| upper.checked_mul(3)))
+    }
 }
 
-impl<T> StringT for Decode<T>
+impl<T, const F: bool, S> StringExtT for Encode<T, F, S>
 where
-    T: AsRef<str>,
+    T: StringT,
+    S: EncodeSetT,
 {
-    #[inline]
-    fn encode_to_buf(self, string: &mut Vec<u8>) {
-        let encoded_bytes = self.inner.as_ref().as_bytes();
+}
+
+/// `%XX` of each byte.
+const ESCAPED: [[u8; 3]; 256] = {
+    const HEX_CHARS_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+    let mut escaped = [[0; 3]; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        escaped[byte] = [b'%', HEX_CHARS_UPPER[byte >> 4], HEX_CHARS_UPPER[byte & 15]];
+        byte += 1;
+    }
+    escaped
+};
+
+#[inline(always)]
+/// Returns `true` if the byte is not written as is.
+const fn needs_rewrite<const F: bool>(table: &[bool; 256], byte: u8) -> bool {
+    if F {
+        // Space to `+`, and `+` is always encoded.
+        byte == b' ' || byte == b'+' || !table[byte as usize]
+    } else {
+        !table[byte as usize]
+    }
+}
+
+/// Percent-encode the bytes written since `start` in place, from back to
+/// front.
+///
+/// Runs of bytes kept as is are moved at once, and the escapes come from a
+/// lookup table.
+pub(crate) fn encode_in_place<B: BufT, const F: bool, S: EncodeSetT>(string: &mut B, start: usize) {
+    let end = string.len();
+
+    let Some(written) = string.as_mut_slice().get(start..end) else {
+        return;
+    };
+
+    let extra_len: usize = written
+        .iter()
+        .filter(|&&byte| needs_rewrite::<F>(&S::TABLE, byte) && !(F && byte == b' '))
+        .count()
+        * 2;
+
+    let mut read = end;
+    let mut write = end + extra_len;
+
+    string.resize(write, 0);
+
+    let Some(buf) = string.as_mut_slice().get_mut(..write) else {
+        return;
+    };
 
-        let mut encoded_bytes_iter = encoded_bytes.split(|&c| c == b'%');
-        if let Some(non_escaped_part) = encoded_bytes_iter.next() {
-            string.extend(non_escaped_part);
+    // Once `read` meets `write`, the remaining bytes need no moving.
+    while read < write {
+        read -= 1;
+
+        let byte = buf[read];
+
+        if !needs_rewrite::<F>(&S::TABLE, byte) {
+            // Move the whole run at once.
+            let run_start = buf[start..read]
+                .iter()
+                .rposition(|&byte| needs_rewrite::<F>(&S::TABLE, byte))
+                .map_or(start, |idx| start + idx + 1);
+
+            write -= read + 1 - run_start;
+            buf.copy_within(run_start..=read, write);
+
+            read = run_start;
+        } else if F && byte == b' ' {
+            write -= 1;
+            buf[write] = b'+';
         } else {
-            return;
+            write -= 3;
+            buf[write..write + 3].copy_from_slice(&ESCAPED[byte as usize]);
+        }
+    }
+
+    // Spaces there can be rewritten without moving.
+    if F {
+        buf[start..read]
+            .iter_mut()
+            .filter(|byte| **byte == b' ')
+            .for_each(|byte| *byte = b'+');
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// `string` which is to be decoded.
+///
+/// As a [`StringT`], malformed escapes like `%zz` are kept as is, and decoded
+/// bytes which are not valid UTF-8 are replaced with `U+FFFD`, so that the
+/// output is always valid UTF-8. See [`Decode::try_decode`] for a strict one.
+///
+/// # Generic
+///
+/// - F: `application/x-www-form-urlencoded` mode, `+` is decoded as space,
+///   default false.
+///
+/// # Example
+///
+/// ```
+/// # use macro_toolset::{urlencoding_str, string::{urlencoding::DecodeError, StringExtT}};
+/// assert_eq!(urlencoding_str!(D: "a%20b%zz%FF").to_string_ext(), "a b%zz\u{FFFD}");
+/// assert_eq!(urlencoding_str!(D: "a%20b").try_decode().unwrap(), "a b");
+/// assert_eq!(
+///     urlencoding_str!(D: "a%20b%zz").try_decode(),
+///     Err(DecodeError::InvalidEscape { offset: 5 })
+/// );
+/// assert_eq!(
+///     urlencoding_str!(D: "a%20b%FF").try_decode(),
+///     Err(DecodeError::InvalidUtf8 { offset: 5 })
+/// );
+/// ```
+#[repr(transparent)]
+pub struct Decode<T, const F: bool = false> {
+    /// Inner value
+    pub inner: T,
+}
+
+impl<T> Decode<T> {
+    #[inline]
+    /// Create a new [`Decode`], `+` is kept as is.
+    pub const fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, const F: bool> Decode<T, F> {
+    #[inline]
+    /// Set whether to decode `+` as space (form mode).
+    pub fn set_form<const NF: bool>(self) -> Decode<T, NF> {
+        Decode { inner: self.inner }
+    }
+}
+
+impl<T: AsRef<str>, const F: bool> Decode<T, F> {
+    /// Decode strictly to the given buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] if there's any malformed escape or the decoded
+    /// bytes are not valid UTF-8, and the buffer is left unchanged.
+    pub fn try_decode_to_buf<B: BufT>(&self, string: &mut B) -> Result<(), DecodeError> {
+        let input = self.inner.as_ref().as_bytes();
+        let start = string.len();
+
+        if let Err(e) = decode_to_buf::<B, F, true>(input, string) {
+            string.truncate(start);
+            return Err(e);
         }
-        for escaped_part in encoded_bytes_iter {
-            let decoded = escaped_part.get(0..=1).and_then(|escaped_part| {
-                Some((
-                    from_hex_digit(escaped_part[0])?,
-                    from_hex_digit(escaped_part[1])?,
-                ))
+
+        if let Some(Err(e)) = string.as_mut_slice().get(start..).map(std::str::from_utf8) {
+            string.truncate(start);
+            return Err(DecodeError::InvalidUtf8 {
+                offset: input_offset(input, e.valid_up_to()),
             });
-            if let Some(decoded) = decoded {
-                string.push((decoded.0 << 4) | decoded.1);
-                if let Some(non_escaped_part) = escaped_part.get(2..) {
-                    string.extend(non_escaped_part);
-                }
-            } else {
-                // Error, keep it.
-                string.extend(b"%");
-                string.extend(escaped_part);
-            }
         }
+
+        Ok(())
+    }
+
+    /// Decode strictly, borrowing the inner value if it needs no decoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecodeError`] if there's any malformed escape or the decoded
+    /// bytes are not valid UTF-8.
+    pub fn try_decode(&self) -> Result<Cow<'_, str>, DecodeError> {
+        if is_plain::<F>(self.inner.as_ref()) {
+            return Ok(Cow::Borrowed(self.inner.as_ref()));
+        }
+
+        let mut string = Vec::with_capacity(self.inner.as_ref().len());
+        self.try_decode_to_buf(&mut string)?;
+
+        // SAFETY: checked in `try_decode_to_buf`.
+        #[allow(unsafe_code)]
+        Ok(Cow::Owned(unsafe { String::from_utf8_unchecked(string) }))
+    }
+
+    /// Decode lossily, borrowing the inner value if it needs no decoding.
+    ///
+    /// Malformed escapes are kept as is, and invalid UTF-8 sequences are
+    /// replaced with `U+FFFD`, the same as the [`StringT`] implementation.
+    pub fn decode_lossy(&self) -> Cow<'_, str> {
+        decode_lossy::<F>(self.inner.as_ref())
+    }
+}
+
+impl<T> From<T> for Decode<T> {
+    #[inline]
+    fn from(inner: T) -> Self {
+        Self::new(inner)
     }
+}
 
+impl_wrapper!([T, const F: bool] Decode<T, F> => T);
+
+impl<T, const F: bool> StringT for Decode<T, F>
+where
+    T: AsRef<str>,
+{
     #[inline]
-    fn encode_to_buf_with_separator(self, string: &mut Vec<u8>, separator: &str) {
-        self.encode_to_buf(string);
-        string.extend(separator.as_bytes());
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        decode_lossy_to_buf::<B, F>(self.inner.as_ref().as_bytes(), string);
     }
 
     #[inline]
-    fn encode_to_bytes_buf(self, string: &mut bytes::BytesMut) {
-        let encoded_bytes = self.inner.as_ref().as_bytes();
+    fn len_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.as_ref().len();
 
-        let mut encoded_bytes_iter = encoded_bytes.split(|&c| c == b'%');
-        if let Some(non_escaped_part) = encoded_bytes_iter.next() {
-            string.extend(non_escaped_part);
-        } else {
-            return;
+        // Each 3 bytes `%XX` will be decoded to 1 byte, or 3 bytes `U+FFFD` at
+        // most.
+        (len.div_ceil(3), Some(len))
+    }
+}
+
+impl<T, const F: bool> StringExtT for Decode<T, F> where T: AsRef<str> {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Error returned by strict decoding, see [`Decode::try_decode`].
+pub enum DecodeError {
+    /// Malformed escape, `%` not followed by 2 hex digits, at the given byte
+    /// offset of the input.
+    InvalidEscape {
+        /// Byte offset of the `%` in the input.
+        offset: usize,
+    },
+
+    /// The decoded bytes are not valid UTF-8.
+    InvalidUtf8 {
+        /// Byte offset in the input where the invalid UTF-8 sequence starts.
+        offset: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEscape { offset } => {
+                write!(f, "invalid percent escape at offset {offset}")
+            }
+            Self::InvalidUtf8 { offset } => {
+                write!(f, "decoded bytes are not valid UTF-8 at offset {offset}")
+            }
         }
-        for escaped_part in encoded_bytes_iter {
-            let decoded = escaped_part.get(0..=1).and_then(|escaped_part| {
-                Some((
-                    from_hex_digit(escaped_part[0])?,
-                    from_hex_digit(escaped_part[1])?,
-                ))
-            });
-            if let Some(decoded) = decoded {
-                string.extend(Some((decoded.0 << 4) | decoded.1));
-                if let Some(non_escaped_part) = escaped_part.get(2..) {
-                    string.extend(non_escaped_part);
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decode the input to the buffer, the output may be invalid UTF-8.
+///
+/// If `STRICT`, returns error at the first malformed escape, or keep it as is.
+fn decode_to_buf<B: BufT, const F: bool, const STRICT: bool>(
+    input: &[u8],
+    string: &mut B,
+) -> Result<(), DecodeError> {
+    string.reserve(input.len());
+
+    let mut idx = 0;
+
+    while idx < input.len() {
+        // Copy the plain run at once.
+        let run_len = input[idx..]
+            .iter()
+            .position(|&byte| byte == b'%' || (F && byte == b'+'))
+            .unwrap_or(input.len() - idx);
+
+        string.extend_from_slice(&input[idx..idx + run_len]);
+        idx += run_len;
+
+        match input.get(idx) {
+            None => break,
+            Some(b'+') => {
+                string.push(b' ');
+                idx += 1;
+            }
+            Some(_) => {
+                let decoded = input.get(idx + 1..idx + 3).and_then(|escaped| {
+                    Some((from_hex_digit(escaped[0])? << 4) | from_hex_digit(escaped[1])?)
+                });
+
+                match decoded {
+                    Some(byte) => {
+                        string.push(byte);
+                        idx += 3;
+                    }
+                    None if STRICT => return Err(DecodeError::InvalidEscape { offset: idx }),
+                    None => {
+                        // Malformed, keep it.
+                        string.push(b'%');
+                        idx += 1;
+                    }
                 }
-            } else {
-                // Error, keep it.
-                string.extend(b"%");
-                string.extend(escaped_part);
             }
         }
     }
 
-    #[inline]
-    fn encode_to_bytes_buf_with_separator(self, string: &mut bytes::BytesMut, separator: &str) {
-        self.encode_to_bytes_buf(string);
-        string.extend(separator.as_bytes());
+    Ok(())
+}
+
+/// Decode the input to the buffer, malformed escapes are kept as is and
+/// invalid UTF-8 sequences are replaced with `U+FFFD`.
+fn decode_lossy_to_buf<B: BufT, const F: bool>(input: &[u8], string: &mut B) {
+    let start = string.len();
+
+    let _ = decode_to_buf::<B, F, false>(input, string);
+
+    let Some(Err(e)) = string.as_mut_slice().get(start..).map(std::str::from_utf8) else {
+        return;
+    };
+
+    // Rare case, just fix up the invalid tail.
+    let invalid_start = start + e.valid_up_to();
+    let tail = string.as_mut_slice()[invalid_start..].to_vec();
+
+    string.truncate(invalid_start);
+    string.extend_from_slice(String::from_utf8_lossy(&tail).as_bytes());
+}
+
+#[inline]
+/// Returns `true` if the input needs no decoding at all.
+fn is_plain<const F: bool>(input: &str) -> bool {
+    !input
+        .bytes()
+        .any(|byte| byte == b'%' || (F && byte == b'+'))
+}
+
+/// Decode lossily, borrowing the input if it needs no decoding.
+pub(crate) fn decode_lossy<const F: bool>(input: &str) -> Cow<'_, str> {
+    if is_plain::<F>(input) {
+        return Cow::Borrowed(input);
     }
+
+    let mut string = Vec::with_capacity(input.len());
+    decode_lossy_to_buf::<_, F>(input.as_bytes(), &mut string);
+
+    // SAFETY: `decode_lossy_to_buf` always outputs valid UTF-8.
+    #[allow(unsafe_code)]
+    Cow::Owned(unsafe { String::from_utf8_unchecked(string) })
 }
 
-impl<T> StringExtT for Decode<T> where T: AsRef<str> {}
+/// Returns the byte offset in the input which is decoded to the byte at
+/// `decoded_idx`, the input should contain no malformed escape.
+fn input_offset(input: &[u8], decoded_idx: usize) -> usize {
+    (0..decoded_idx).fold(0, |offset, _| match input[offset] {
+        b'%' => offset + 3,
+        _ => offset + 1,
+    })
+}
 
 #[inline(always)]
 const fn from_hex_digit(digit: u8) -> Option<u8> {
@@ -183,3 +533,230 @@ const fn from_hex_digit(digit: u8) -> Option<u8> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use super::{set::*, Decode, DecodeError, Encode};
+    use crate::string::general::iterator::IterWrapper;
+    use crate::string::PushAnyT;
+    use crate::string::StringExtT;
+    use crate::string::StringT;
+
+    const CASES: &[&str] = &[
+        "",
+        "hello",
+        "a b+c",
+        "  ++  ",
+        "key=value&k2=v 2",
+        "你好, 世界!",
+        "~-._*'()/?#[]@",
+        "😀 emoji",
+    ];
+
+    #[test]
+    fn test_encode_decode() {
+        for &case in CASES {
+            let encoded = urlencoding_str!(E: case).to_string_ext();
+            assert_eq!(encoded, urlencoding::encode(case));
+            assert_eq!(urlencoding_str!(D: &encoded).to_string_ext(), case);
+        }
+
+        // `+` is kept as is.
+        assert_eq!(urlencoding_str!(D: "a+b%20c").to_string_ext(), "a+b c");
+    }
+
+    #[test]
+    fn test_form_encode_decode() {
+        for &case in CASES {
+            let encoded = urlencoding_str!(F: case).to_string_ext();
+            assert_eq!(encoded, urlencoding::encode(case).replace("%20", "+"));
+            assert!(!encoded.contains(' '));
+            assert_eq!(urlencoding_str!(FD: &encoded).to_string_ext(), case);
+            assert_eq!(
+                urlencoding_str!(FD: &encoded).to_string_ext(),
+                urlencoding::decode(&encoded.replace('+', "%20")).unwrap()
+            );
+        }
+
+        assert_eq!(urlencoding_str!(FD: "a+b%20c%2B").to_string_ext(), "a b c+");
+        // Malformed escapes are kept, while `+` is still decoded.
+        assert_eq!(
+            urlencoding_str!(FD: "100%+a%zz+").to_string_ext(),
+            "100% a%zz "
+        );
+    }
+
+    #[test]
+    fn test_encode_in_place() {
+        // Existing content is untouched, consistent across targets.
+        let mut string = String::from("a b?");
+        string.push_any(Encode::new(("a b?", "你好", 1u8, "~")));
+        assert_eq!(string, "a b?a%20b%3F%E4%BD%A0%E5%A5%BD1~");
+
+        let mut bytes = bytes::BytesMut::from(&b"a b?"[..]);
+        bytes.push_any(Encode::new(("a b?", "你好", 1u8, "~")));
+        assert_eq!(&bytes[..], string.as_bytes());
+
+        // Long runs.
+        let data = "x".repeat(100) + "/" + &"y".repeat(100) + " ";
+        assert_eq!(
+            Encode::new(&data).to_string_ext(),
+            urlencoding::encode(&data)
+        );
+        assert_eq!(
+            Encode::new(&data).set_form::<true>().to_string_ext(),
+            urlencoding::encode(&data).replace("%20", "+")
+        );
+    }
+
+    #[test]
+    fn test_encode_set() {
+        let data = "a b/c?d=e&f+g#h:i@j";
+
+        let encode = |set: fn(Encode<&'static str>) -> String| set(Encode::new(data));
+
+        assert_eq!(
+            encode(|e| e.to_string_ext()),
+            "a%20b%2Fc%3Fd%3De%26f%2Bg%23h%3Ai%40j"
+        );
+        assert_eq!(
+            encode(|e| e.set_encode_set::<PathSegment>().to_string_ext()),
+            "a%20b%2Fc%3Fd=e&f+g%23h:i@j"
+        );
+        assert_eq!(
+            encode(|e| e.set_encode_set::<Path>().to_string_ext()),
+            "a%20b/c%3Fd=e&f+g%23h:i@j"
+        );
+        assert_eq!(
+            encode(|e| e.set_encode_set::<Query>().to_string_ext()),
+            "a%20b/c?d=e&f+g%23h:i@j"
+        );
+        assert_eq!(
+            encode(|e| e.set_encode_set::<QueryValue>().to_string_ext()),
+            "a%20b/c?d%3De%26f%2Bg%23h:i@j"
+        );
+        assert_eq!(
+            encode(|e| e.set_encode_set::<Userinfo>().to_string_ext()),
+            "a%20b%2Fc%3Fd=e&f+g%23h:i%40j"
+        );
+
+        // Form mode always encodes `+`, even if the set keeps it.
+        assert_eq!(
+            encode(|e| e
+                .set_encode_set::<Query>()
+                .set_form::<true>()
+                .to_string_ext()),
+            "a+b/c?d=e&f%2Bg%23h:i@j"
+        );
+
+        // Only spaces to rewrite in form mode.
+        assert_eq!(Encode::new(" a ").set_form::<true>().to_string_ext(), "+a+");
+
+        // Non-ASCII bytes are always encoded.
+        assert_eq!(
+            Encode::new("你").set_encode_set::<Path>().to_string_ext(),
+            "%E4%BD%A0"
+        );
+    }
+
+    #[test]
+    fn test_decode_strict() {
+        let decode = |s: &'static str| Decode::new(s).try_decode().map(Cow::into_owned);
+
+        assert_eq!(decode("a+b").unwrap(), "a+b");
+        assert_eq!(decode("%E4%BD%A0%e5%a5%bd").unwrap(), "你好");
+        assert_eq!(
+            Decode::new("a+b%2B")
+                .set_form::<true>()
+                .try_decode()
+                .unwrap(),
+            "a b+"
+        );
+
+        assert_eq!(decode("%"), Err(DecodeError::InvalidEscape { offset: 0 }));
+        assert_eq!(
+            decode("ab%4"),
+            Err(DecodeError::InvalidEscape { offset: 2 })
+        );
+        assert_eq!(
+            decode("你%g0"),
+            Err(DecodeError::InvalidEscape { offset: 3 })
+        );
+        assert_eq!(decode("%FF"), Err(DecodeError::InvalidUtf8 { offset: 0 }));
+        assert_eq!(
+            decode("a%E4%BD%A0%E4%BDb"),
+            Err(DecodeError::InvalidUtf8 { offset: 10 })
+        );
+        assert_eq!(
+            DecodeError::InvalidEscape { offset: 2 }.to_string(),
+            "invalid percent escape at offset 2"
+        );
+
+        // The buffer is left unchanged on error.
+        let mut string = b"head".to_vec();
+        assert!(Decode::new("%41%FF")
+            .try_decode_to_buf(&mut string)
+            .is_err());
+        assert_eq!(string, b"head");
+        assert_eq!(Decode::new("%41").try_decode_to_buf(&mut string), Ok(()));
+        assert_eq!(string, b"headA");
+    }
+
+    #[test]
+    fn test_decode_lossy() {
+        assert_eq!(Decode::new("%FF%41").decode_lossy(), "\u{FFFD}A");
+        assert_eq!(
+            Decode::new("%E4%BD%A0%E4%BD%zz").decode_lossy(),
+            "你\u{FFFD}%zz"
+        );
+
+        // Never pushes invalid UTF-8 into a `String`.
+        let mut string = String::from("你");
+        string.push_any(Decode::new("%E4%BD"));
+        string.push_any(Decode::new("%A0+%C3%28"));
+        assert_eq!(string, "你\u{FFFD}\u{FFFD}+\u{FFFD}(");
+    }
+
+    #[test]
+    fn test_encode_wrapper() {
+        let mut encode = Encode::new("a b").set_encode_set::<Path>();
+        assert_eq!(*encode, "a b");
+        assert_eq!(encode.len(), 3);
+
+        *encode = "a/b c";
+        assert_eq!(encode.to_string_ext(), "a/b%20c");
+        assert_eq!(Encode::from("c").as_ref(), &"c");
+    }
+
+    #[test]
+    fn test_decode_wrapper() {
+        let decode: Decode<_> = Decode { inner: "a%20b" };
+        let mut decode = decode.set_form::<true>();
+        assert_eq!(*decode, "a%20b");
+        assert_eq!(decode.len(), 5);
+
+        *decode = "a+b";
+        assert_eq!(decode.to_string_ext(), "a b");
+        assert_eq!(
+            *std::borrow::Borrow::<&str>::borrow(&Decode::from("c")),
+            "c"
+        );
+    }
+
+    #[test]
+    fn test_encode_write_to_fmt() {
+        let value = || Encode::new(IterWrapper::new((0..2000u32).map(|n| (n, " /你"))));
+        let mut string = String::new();
+        value().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, value().to_string_ext());
+        assert!(!string.contains(' '));
+
+        let value =
+            || Encode::new(IterWrapper::new((0..2000u32).map(|n| (n, ' ')))).set_form::<true>();
+        let mut string = String::new();
+        value().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, value().to_string_ext());
+    }
+}
@@ -0,0 +1,511 @@
+//! Query string utilities.
+
+use std::{borrow::Cow, iter::FusedIterator, marker::PhantomData, ops::Range};
+
+use super::{
+    decode_lossy, encode_in_place,
+    set::{EncodeSetT, Unreserved},
+    Decode,
+};
+use crate::string::{BufT, StringExtT, StringT, Uncommitted};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Order of the pairs in [`QueryString`].
+pub enum QueryOrder {
+    #[default]
+    /// As inserted.
+    Insertion,
+
+    /// Sorted by the encoded key then the encoded value (byte-wise), like what
+    /// is required by canonical requests for signing, e.g. AWS Signature Version 4.
+    Sorted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How `None` values pushed by [`QueryString::pair_opt`] are written.
+pub enum NoneValue {
+    #[default]
+    /// Skip the whole pair.
+    Skip,
+
+    /// Write the key with an empty value, i.e. `key=`.
+    Empty,
+}
+
+#[derive(Debug, Clone)]
+/// Query string builder, writes `k1=v1&k2=v2` (without the leading `?`).
+///
+/// Both keys and values can be any [`StringT`] and are percent-encoded with
+/// the [encoding set](super::set), [`Unreserved`] by default. Repeated keys
+/// and empty values are kept.
+///
+/// # Example
+///
+/// ```
+/// # use macro_toolset::string::{urlencoding::{NoneValue, QueryOrder, QueryString}, StringExtT};
+/// let query = QueryString::new()
+///     .pair("b", "x y")
+///     .pair("a", "2")
+///     .pair_opt("c", None)
+///     .pair("a", "1")
+///     .pair("d", "");
+///
+/// assert_eq!(query.clone().to_string_ext(), "b=x%20y&a=2&a=1&d=");
+/// assert_eq!(
+///     query.order(QueryOrder::Sorted).none_value(NoneValue::Empty).to_string_ext(),
+///     "a=1&a=2&b=x%20y&c=&d="
+/// );
+/// ```
+pub struct QueryString<K, V, S = Unreserved> {
+    pairs: Vec<(K, Option<V>)>,
+    order: QueryOrder,
+    none_value: NoneValue,
+    encode_set: PhantomData<S>,
+}
+
+impl<K, V> QueryString<K, V> {
+    #[inline]
+    /// Create a new empty [`QueryString`], in insertion order and `None`
+    /// values are skipped.
+    pub const fn new() -> Self {
+        Self {
+            pairs: Vec::new(),
+            order: QueryOrder::Insertion,
+            none_value: NoneValue::Skip,
+            encode_set: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Default for QueryString<K, V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> QueryString<K, V, S> {
+    #[inline]
+    /// Set the order of the pairs.
+    pub fn order(mut self, order: QueryOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    #[inline]
+    /// Set how `None` values are written.
+    pub fn none_value(mut self, none_value: NoneValue) -> Self {
+        self.none_value = none_value;
+        self
+    }
+
+    #[inline]
+    /// Set the [encoding set](super::set) of both keys and values.
+    pub fn set_encode_set<NS: EncodeSetT>(self) -> QueryString<K, V, NS> {
+        QueryString {
+            pairs: self.pairs,
+            order: self.order,
+            none_value: self.none_value,
+            encode_set: PhantomData,
+        }
+    }
+
+    #[inline]
+    /// Append a pair.
+    pub fn pair(mut self, key: K, value: V) -> Self {
+        self.push(key, value);
+        self
+    }
+
+    #[inline]
+    /// Append a pair with optional value, see [`NoneValue`].
+    pub fn pair_opt(mut self, key: K, value: Option<V>) -> Self {
+        self.push_opt(key, value);
+        self
+    }
+
+    #[inline]
+    /// Append a pair.
+    pub fn push(&mut self, key: K, value: V) {
+        self.pairs.push((key, Some(value)));
+    }
+
+    #[inline]
+    /// Append a pair with optional value, see [`NoneValue`].
+    pub fn push_opt(&mut self, key: K, value: Option<V>) {
+        self.pairs.push((key, value));
+    }
+
+    #[inline]
+    /// Returns the number of pairs, including the ones with `None` value.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    #[inline]
+    /// Returns `true` if there's no pair.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for QueryString<K, V> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut query = Self::new();
+        query.extend(iter);
+        query
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for QueryString<K, V, S> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.pairs
+            .extend(iter.into_iter().map(|(key, value)| (key, Some(value))));
+    }
+}
+
+impl<K, V, S> QueryString<K, V, S>
+where
+    K: StringT,
+    V: StringT,
+    S: EncodeSetT,
+{
+    #[inline]
+    /// Returns the pairs to be written.
+    fn into_pairs(self) -> impl Iterator<Item = (K, Option<V>)> {
+        let skip_none = self.none_value == NoneValue::Skip;
+
+        self.pairs
+            .into_iter()
+            .filter(move |(_, value)| !(skip_none && value.is_none()))
+    }
+
+    #[inline]
+    /// Write `key=value`, percent-encoded.
+    fn encode_pair<B: BufT>(
+        key: K,
+        value: Option<V>,
+        string: &mut B,
+    ) -> (Range<usize>, Range<usize>) {
+        let key_start = string.len();
+        key.encode_to_buf(&mut Uncommitted(&mut *string));
+        encode_in_place::<B, false, S>(string, key_start);
+        let key_end = string.len();
+
+        string.push(b'=');
+
+        let value_start = string.len();
+        value.encode_to_buf(&mut Uncommitted(&mut *string));
+        encode_in_place::<B, false, S>(string, value_start);
+
+        (key_start..key_end, value_start..string.len())
+    }
+}
+
+impl<K, V, S> StringT for QueryString<K, V, S>
+where
+    K: StringT,
+    V: StringT,
+    S: EncodeSetT,
+{
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        match self.order {
+            QueryOrder::Insertion => {
+                for (idx, (key, value)) in self.into_pairs().enumerate() {
+                    if idx > 0 {
+                        string.push(b'&');
+                    }
+
+                    Self::encode_pair(key, value, string);
+                }
+            }
+            QueryOrder::Sorted => {
+                // Encode all pairs first, then sort them.
+                let mut scratch = Vec::with_capacity(self.pairs.len() * 16);
+                let mut ranges = self
+                    .into_pairs()
+                    .map(|(key, value)| Self::encode_pair(key, value, &mut scratch))
+                    .collect::<Vec<_>>();
+
+                ranges.sort_by(|(key_a, value_a), (key_b, value_b)| {
+                    scratch[key_a.clone()]
+                        .cmp(&scratch[key_b.clone()])
+                        .then_with(|| scratch[value_a.clone()].cmp(&scratch[value_b.clone()]))
+                });
+
+                for (idx, (key, value)) in ranges.into_iter().enumerate() {
+                    if idx > 0 {
+                        string.push(b'&');
+                    }
+
+                    string.extend_from_slice(&scratch[key.start..value.end]);
+                }
+            }
+        }
+    }
+}
+
+impl<K, V, S> StringExtT for QueryString<K, V, S>
+where
+    K: StringT,
+    V: StringT,
+    S: EncodeSetT,
+{
+}
+
+// =============================================================================
+
+#[inline]
+/// Parse the query string, see [`QueryPairs`].
+pub fn parse_query(query: &str) -> QueryPairs<'_> {
+    QueryPairs::new(query)
+}
+
+#[derive(Debug, Clone)]
+/// Iterator over the decoded `(key, value)` pairs of a query string, like
+/// `a=1&b=x%20y&c`.
+///
+/// - A leading `?` is ignored.
+/// - Empty segments (like in `a=1&&b=2&`) are skipped.
+/// - Segments without `=` have empty value.
+/// - Repeated keys are all kept, in order.
+/// - Keys and values are decoded lossily (see [`Decode`]), borrowed from the
+///   query string unless decoding is needed.
+///
+/// # Generic
+///
+/// - F: `application/x-www-form-urlencoded` mode, `+` is decoded as space,
+///   default true like what browsers do for `URLSearchParams`.
+///
+/// # Example
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use macro_toolset::string::{urlencoding::parse_query, PushAnyT};
+/// let pairs = parse_query("?a=1&&b=x+y%20z&a&=v").collect::<Vec<_>>();
+/// assert_eq!(
+///     pairs,
+///     [("a", "1"), ("b", "x y z"), ("a", ""), ("", "v")]
+///         .map(|(k, v)| (Cow::Borrowed(k), Cow::Borrowed(v)))
+/// );
+/// assert!(matches!(pairs[0].0, Cow::Borrowed(_)));
+///
+/// // `+` is kept as is in non-form mode.
+/// let mut pairs = parse_query("b=x+y%20z").set_form::<false>();
+/// assert_eq!(pairs.next().unwrap().1, "x+y z");
+///
+/// // Write decoded pairs directly, with no intermediate allocation.
+/// let mut string = String::new();
+/// for (key, value) in parse_query("k%201=v%2B1").raw() {
+///     string.push_any((key, ": ", value));
+/// }
+/// assert_eq!(string, "k 1: v+1");
+/// ```
+pub struct QueryPairs<'q, const F: bool = true> {
+    rest: &'q str,
+}
+
+impl<'q> QueryPairs<'q> {
+    #[inline]
+    /// Create a new [`QueryPairs`] in form mode.
+    pub fn new(query: &'q str) -> Self {
+        Self {
+            rest: query.strip_prefix('?').unwrap_or(query),
+        }
+    }
+}
+
+impl<'q, const F: bool> QueryPairs<'q, F> {
+    #[inline]
+    /// Set whether to decode `+` as space (form mode).
+    pub const fn set_form<const NF: bool>(self) -> QueryPairs<'q, NF> {
+        QueryPairs { rest: self.rest }
+    }
+
+    #[inline]
+    /// Iterate over the raw `(key, value)` pairs wrapped in [`Decode`], which
+    /// can be written to any buffer as decoded directly.
+    pub fn raw(self) -> impl Iterator<Item = (Decode<&'q str, F>, Decode<&'q str, F>)> + Clone {
+        RawPairs { rest: self.rest }.map(|(key, value)| {
+            (
+                Decode::new(key).set_form::<F>(),
+                Decode::new(value).set_form::<F>(),
+            )
+        })
+    }
+}
+
+impl<'q, const F: bool> Iterator for QueryPairs<'q, F> {
+    type Item = (Cow<'q, str>, Cow<'q, str>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut raw = RawPairs { rest: self.rest };
+        let pair = raw.next();
+        self.rest = raw.rest;
+
+        pair.map(|(key, value)| (decode_lossy::<F>(key), decode_lossy::<F>(value)))
+    }
+}
+
+impl<const F: bool> FusedIterator for QueryPairs<'_, F> {}
+
+#[derive(Debug, Clone)]
+/// Iterator over the raw (not decoded) `(key, value)` pairs.
+struct RawPairs<'q> {
+    rest: &'q str,
+}
+
+impl<'q> Iterator for RawPairs<'q> {
+    type Item = (&'q str, &'q str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let (segment, rest) = self.rest.split_once('&').unwrap_or((self.rest, ""));
+            self.rest = rest;
+
+            if !segment.is_empty() {
+                return Some(segment.split_once('=').unwrap_or((segment, "")));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::string::{general::iterator::IterWrapper, urlencoding::set::QueryValue, PushAnyT};
+
+    #[test]
+    fn test_query_string() {
+        assert_eq!(QueryString::<&str, &str>::new().to_string_ext(), "");
+        assert_eq!(QueryString::new().pair("", "").to_string_ext(), "=");
+
+        let query = QueryString::new()
+            .pair("z", "1")
+            .pair_opt("k y", Some("a&b=c"))
+            .pair_opt("n", None)
+            .pair("你", "")
+            .pair("k y", "a+b");
+
+        assert_eq!(
+            query.clone().to_string_ext(),
+            "z=1&k%20y=a%26b%3Dc&%E4%BD%A0=&k%20y=a%2Bb"
+        );
+        assert_eq!(
+            query.clone().none_value(NoneValue::Empty).to_string_ext(),
+            "z=1&k%20y=a%26b%3Dc&n=&%E4%BD%A0=&k%20y=a%2Bb"
+        );
+        // Sorted by the encoded key then value, repeated keys kept.
+        assert_eq!(
+            query.clone().order(QueryOrder::Sorted).to_string_ext(),
+            "%E4%BD%A0=&k%20y=a%26b%3Dc&k%20y=a%2Bb&z=1"
+        );
+        assert_eq!(
+            query
+                .set_encode_set::<QueryValue>()
+                .order(QueryOrder::Sorted)
+                .none_value(NoneValue::Empty)
+                .to_string_ext(),
+            "%E4%BD%A0=&k%20y=a%26b%3Dc&k%20y=a%2Bb&n=&z=1"
+        );
+    }
+
+    #[test]
+    fn test_query_string_nested() {
+        let query: QueryString<_, _> = [("id", 2u64), ("id", 10), ("page", 1)]
+            .into_iter()
+            .collect();
+
+        // Existing content is untouched.
+        let mut string = "/path?a b".to_string();
+        string.push_any(query.order(QueryOrder::Sorted).with_prefix("&"));
+        assert_eq!(string, "/path?a b&id=10&id=2&page=1");
+
+        assert_eq!(
+            QueryString::new()
+                .pair(("user-", 1u8), ("a", ' '))
+                .to_string_ext(),
+            "user-1=a%20"
+        );
+    }
+
+    #[test]
+    fn test_query_string_write_to_fmt() {
+        fn item(n: u32) -> (u32, &'static str) {
+            (n, " &")
+        }
+
+        let query = || {
+            QueryString::new()
+                .pair("a b", IterWrapper::new((0..2000u32).map(item)))
+                .pair("c", IterWrapper::new((0..10u32).map(item)))
+        };
+        let mut string = String::new();
+        query().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, query().to_string_ext());
+        assert_eq!(string.matches('&').count(), 1);
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let parse = |query| {
+            parse_query(query)
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(parse("").is_empty());
+        assert!(parse("?").is_empty());
+        assert!(parse("&&&").is_empty());
+        assert_eq!(
+            parse("?a=1&a=2&&b&c=&=d&e==f&"),
+            [
+                ("a", "1"),
+                ("a", "2"),
+                ("b", ""),
+                ("c", ""),
+                ("", "d"),
+                ("e", "=f")
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+        // Only the first `?` is ignored.
+        assert_eq!(parse("??a").first().unwrap().0, "?a");
+        assert_eq!(
+            parse("k+1=%E4%BD%A0+%2B%20%zz%FF"),
+            [("k 1".to_string(), "你 + %zz\u{FFFD}".to_string())]
+        );
+        assert_eq!(
+            parse_query("a+b=%2B+").set_form::<false>().next().unwrap(),
+            (Cow::Borrowed("a+b"), Cow::Owned("++".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_query_round_trip() {
+        let query = QueryString::new()
+            .pair("k y", "a&b=c")
+            .pair("你", "")
+            .pair("k y", "a+b")
+            .to_string_ext();
+
+        assert_eq!(
+            parse_query(&query).collect::<Vec<_>>(),
+            [("k y", "a&b=c"), ("你", ""), ("k y", "a+b")]
+                .map(|(k, v)| (Cow::Borrowed(k), Cow::Borrowed(v)))
+        );
+
+        let mut string = String::new();
+        parse_query(&query)
+            .raw()
+            .for_each(|(key, value)| string.push_any((key, "=", value, "\n")));
+        assert_eq!(string, "k y=a&b=c\n你=\nk y=a+b\n");
+    }
+}
@@ -0,0 +1,212 @@
+//! Percent-encoding sets, see [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-3).
+//!
+//! An encoding set tells which ASCII bytes are kept as is, all other bytes
+//! (including all non-ASCII ones) are percent-encoded.
+//!
+//! # Example
+//!
+//! ```
+//! # use macro_toolset::string::{urlencoding::{set::{EncodeSet, EncodeSetT, Path}, Encode}, StringExtT};
+//! assert_eq!(
+//!     Encode::new("/a b/c?d").set_encode_set::<Path>().to_string_ext(),
+//!     "/a%20b/c%3Fd"
+//! );
+//!
+//! // User-defined set.
+//! #[derive(Debug, Clone, Copy)]
+//! struct KeepComma;
+//!
+//! impl EncodeSetT for KeepComma {
+//!     const SET: EncodeSet = EncodeSet::UNRESERVED.keep(b',');
+//! }
+//!
+//! assert_eq!(
+//!     Encode::new("a,b c").set_encode_set::<KeepComma>().to_string_ext(),
+//!     "a,b%20c"
+//! );
+//! ```
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Set of ASCII bytes which are kept as is when percent-encoding.
+pub struct EncodeSet {
+    /// Bit `n` is set if the byte `n` is kept.
+    kept: u128,
+}
+
+impl EncodeSet {
+    /// No byte is kept.
+    pub const EMPTY: Self = Self { kept: 0 };
+
+    /// `ALPHA / DIGIT / "-" / "." / "_" / "~"`
+    pub const UNRESERVED: Self = Self::EMPTY
+        .keep_range(b'0', b'9')
+        .keep_range(b'A', b'Z')
+        .keep_range(b'a', b'z')
+        .keep_all(b"-._~");
+
+    /// `"!" / "$" / "&" / "'" / "(" / ")" / "*" / "+" / "," / ";" / "="`
+    pub const SUB_DELIMS: Self = Self::EMPTY.keep_all(b"!$&'()*+,;=");
+
+    /// `unreserved / sub-delims / ":" / "@"`
+    pub const PCHAR: Self = Self::UNRESERVED.union(Self::SUB_DELIMS).keep_all(b":@");
+
+    #[inline]
+    /// Keep the given ASCII byte, non-ASCII bytes are ignored.
+    pub const fn keep(self, byte: u8) -> Self {
+        if byte < 128 {
+            Self {
+                kept: self.kept | (1 << byte),
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Keep all the given ASCII bytes.
+    pub const fn keep_all(mut self, bytes: &[u8]) -> Self {
+        let mut idx = 0;
+        while idx < bytes.len() {
+            self = self.keep(bytes[idx]);
+            idx += 1;
+        }
+        self
+    }
+
+    /// Keep all the ASCII bytes in `start..=end`.
+    pub const fn keep_range(mut self, start: u8, end: u8) -> Self {
+        let mut byte = start;
+        while byte <= end && byte < 128 {
+            self = self.keep(byte);
+            byte += 1;
+        }
+        self
+    }
+
+    #[inline]
+    /// Percent-encode the given byte, i.e. not to keep it.
+    pub const fn encode(self, byte: u8) -> Self {
+        if byte < 128 {
+            Self {
+                kept: self.kept & !(1 << byte),
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Percent-encode all the given bytes.
+    pub const fn encode_all(mut self, bytes: &[u8]) -> Self {
+        let mut idx = 0;
+        while idx < bytes.len() {
+            self = self.encode(bytes[idx]);
+            idx += 1;
+        }
+        self
+    }
+
+    #[inline]
+    /// Bytes kept in either set.
+    pub const fn union(self, other: Self) -> Self {
+        Self {
+            kept: self.kept | other.kept,
+        }
+    }
+
+    #[inline]
+    /// Returns `true` if the byte is kept as is.
+    pub const fn is_kept(&self, byte: u8) -> bool {
+        byte < 128 && self.kept & (1 << byte) != 0
+    }
+
+    /// Returns the lookup table, whether each byte is kept as is.
+    pub const fn to_table(self) -> [bool; 256] {
+        let mut table = [false; 256];
+        let mut byte = 0;
+        while byte < 128 {
+            table[byte] = self.is_kept(byte as u8);
+            byte += 1;
+        }
+        table
+    }
+}
+
+/// Trait for types carrying an [`EncodeSet`] as const data, for
+/// [`Encode::set_encode_set`](super::Encode::set_encode_set).
+pub trait EncodeSetT {
+    /// The encoding set.
+    const SET: EncodeSet;
+
+    /// The lookup table of [`SET`](Self::SET), no need to override.
+    const TABLE: [bool; 256] = Self::SET.to_table();
+}
+
+macro_rules! encode_set {
+    ($($(#[$meta:meta])* $name:ident => $set:expr;)*) => {
+        $(
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            $(#[$meta])*
+            pub struct $name;
+
+            impl EncodeSetT for $name {
+                const SET: EncodeSet = $set;
+            }
+        )*
+    };
+}
+
+encode_set! {
+    /// Only unreserved characters are kept, the default one, same as
+    /// JavaScript's `encodeURIComponent` except for `!'()*`.
+    Unreserved => EncodeSet::UNRESERVED;
+
+    /// A single path segment, `pchar`, `/` is encoded.
+    PathSegment => EncodeSet::PCHAR;
+
+    /// The whole path, `pchar / "/"`.
+    Path => EncodeSet::PCHAR.keep(b'/');
+
+    /// The whole query, `pchar / "/" / "?"`.
+    Query => EncodeSet::PCHAR.keep_all(b"/?");
+
+    /// A key or value in the query, like [`Query`] but `&`, `=` and `+` are
+    /// encoded.
+    QueryValue => EncodeSet::PCHAR.keep_all(b"/?").encode_all(b"&=+");
+
+    /// The fragment, `pchar / "/" / "?"`.
+    Fragment => EncodeSet::PCHAR.keep_all(b"/?");
+
+    /// The userinfo, `unreserved / sub-delims / ":"`.
+    Userinfo => EncodeSet::UNRESERVED.union(EncodeSet::SUB_DELIMS).keep(b':');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_set() {
+        let kept = |set: EncodeSet| {
+            (0..=255u8)
+                .filter(|&byte| set.is_kept(byte) && !byte.is_ascii_alphanumeric())
+                .map(char::from)
+                .collect::<String>()
+        };
+
+        assert_eq!(kept(Unreserved::SET), "-._~");
+        assert_eq!(kept(PathSegment::SET), "!$&'()*+,-.:;=@_~");
+        assert_eq!(kept(Path::SET), "!$&'()*+,-./:;=@_~");
+        assert_eq!(kept(Query::SET), "!$&'()*+,-./:;=?@_~");
+        assert_eq!(kept(QueryValue::SET), "!$'()*,-./:;?@_~");
+        assert_eq!(kept(Fragment::SET), kept(Query::SET));
+        assert_eq!(kept(Userinfo::SET), "!$&'()*+,-.:;=_~");
+
+        assert_eq!(
+            (0..=255u8)
+                .filter(|&byte| Unreserved::SET.is_kept(byte))
+                .count(),
+            66
+        );
+        assert_eq!(EncodeSet::EMPTY.keep(0xFF), EncodeSet::EMPTY);
+        assert!(!EncodeSet::EMPTY.keep(b'a').encode(b'a').is_kept(b'a'));
+    }
+}
@@ -0,0 +1,328 @@
+//! URL builder.
+
+use std::iter::Map;
+
+use super::{
+    set::{EncodeSet, EncodeSetT, Fragment, PathSegment, QueryValue, Userinfo},
+    Encode,
+};
+use crate::string::{general::iterator::IterWrapper, BufT, StringExtT, StringT};
+
+#[derive(Debug, Clone, Copy)]
+/// The user name in userinfo, like [`Userinfo`] but `:` is encoded.
+struct User;
+
+impl EncodeSetT for User {
+    const SET: EncodeSet = Userinfo::SET.encode(b':');
+}
+
+/// `/segment`
+type Segment<T> = (char, Encode<T, false, PathSegment>);
+
+/// `/segment` of each item.
+type Segments<I> = IterWrapper<
+    Map<
+        <I as IntoIterator>::IntoIter,
+        fn(<I as IntoIterator>::Item) -> Segment<<I as IntoIterator>::Item>,
+    >,
+>;
+
+/// `?key=value` or `&key=value`, or nothing.
+type QueryPair<K, V> = Option<(
+    char,
+    Encode<K, false, QueryValue>,
+    char,
+    Encode<V, false, QueryValue>,
+)>;
+
+#[derive(Debug, Clone)]
+/// URL builder, writes `scheme://[userinfo@]host[:port][/path][?query][#fragment]`.
+///
+/// - The host is written as is, e.g. `example.com` or `[::1]`.
+/// - Path segments, query pairs and the fragment can be any [`StringT`], and
+///   are percent-encoded with [`PathSegment`], [`QueryValue`] and [`Fragment`]
+///   respectively.
+///
+/// The builder writes into any [`PushAnyT`](crate::string::PushAnyT) target,
+/// and no intermediate buffer is allocated.
+///
+/// # Example
+///
+/// ```
+/// # use macro_toolset::string::{urlencoding::UrlBuilder, StringExtT};
+/// let url = UrlBuilder::new("https", "example.com")
+///     .port(8443)
+///     .segment("users")
+///     .segment(("user ", 42u64))
+///     .segments(["a/b", "c"])
+///     .query("q", "x&y")
+///     .query_opt("page", Some(2u8))
+///     .query_opt("limit", None::<u8>)
+///     .fragment("top 1");
+///
+/// assert_eq!(
+///     url.to_string_ext(),
+///     "https://example.com:8443/users/user%2042/a%2Fb/c?q=x%26y&page=2#top%201"
+/// );
+/// ```
+pub struct UrlBuilder<'a, H, P = (), Q = (), F = ()> {
+    scheme: &'a str,
+    userinfo: Option<(&'a str, Option<&'a str>)>,
+    host: H,
+    port: Option<u16>,
+    path: P,
+    query: Q,
+    has_query: bool,
+    fragment: F,
+}
+
+impl<'a, H> UrlBuilder<'a, H> {
+    #[inline]
+    /// Create a new [`UrlBuilder`] with given scheme (like `https`) and host.
+    pub const fn new(scheme: &'a str, host: H) -> Self {
+        Self {
+            scheme,
+            userinfo: None,
+            host,
+            port: None,
+            path: (),
+            query: (),
+            has_query: false,
+            fragment: (),
+        }
+    }
+}
+
+impl<'a, H, P, Q, F> UrlBuilder<'a, H, P, Q, F> {
+    #[inline]
+    /// Set the userinfo, the password is deprecated by RFC 3986 though.
+    pub const fn userinfo(mut self, user: &'a str, password: Option<&'a str>) -> Self {
+        self.userinfo = Some((user, password));
+        self
+    }
+
+    #[inline]
+    /// Set the port.
+    pub const fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    #[inline]
+    /// Append a path segment, `/` in it will be encoded.
+    pub fn segment<T>(self, segment: T) -> UrlBuilder<'a, H, (P, Segment<T>), Q, F> {
+        let segment = ('/', Encode::new(segment).set_encode_set::<PathSegment>());
+
+        UrlBuilder {
+            scheme: self.scheme,
+            userinfo: self.userinfo,
+            host: self.host,
+            port: self.port,
+            path: (self.path, segment),
+            query: self.query,
+            has_query: self.has_query,
+            fragment: self.fragment,
+        }
+    }
+
+    #[inline]
+    /// Append path segments, see [`segment`](Self::segment).
+    pub fn segments<I>(self, segments: I) -> UrlBuilder<'a, H, (P, Segments<I>), Q, F>
+    where
+        I: IntoIterator,
+    {
+        let segments = IterWrapper::new(segments.into_iter().map(
+            (|segment: I::Item| ('/', Encode::new(segment).set_encode_set::<PathSegment>()))
+                as fn(_) -> _,
+        ));
+
+        UrlBuilder {
+            scheme: self.scheme,
+            userinfo: self.userinfo,
+            host: self.host,
+            port: self.port,
+            path: (self.path, segments),
+            query: self.query,
+            has_query: self.has_query,
+            fragment: self.fragment,
+        }
+    }
+
+    #[inline]
+    /// Append a query pair.
+    pub fn query<K, V>(self, key: K, value: V) -> UrlBuilder<'a, H, P, (Q, QueryPair<K, V>), F> {
+        self.query_opt(key, Some(value))
+    }
+
+    #[inline]
+    /// Append a query pair, or skip it if the value is `None`.
+    pub fn query_opt<K, V>(
+        self,
+        key: K,
+        value: Option<V>,
+    ) -> UrlBuilder<'a, H, P, (Q, QueryPair<K, V>), F> {
+        let pair = value.map(|value| {
+            (
+                if self.has_query { '&' } else { '?' },
+                Encode::new(key).set_encode_set::<QueryValue>(),
+                '=',
+                Encode::new(value).set_encode_set::<QueryValue>(),
+            )
+        });
+
+        UrlBuilder {
+            scheme: self.scheme,
+            userinfo: self.userinfo,
+            host: self.host,
+            port: self.port,
+            path: self.path,
+            has_query: self.has_query || pair.is_some(),
+            query: (self.query, pair),
+            fragment: self.fragment,
+        }
+    }
+
+    #[inline]
+    /// Set the fragment.
+    pub fn fragment<T>(
+        self,
+        fragment: T,
+    ) -> UrlBuilder<'a, H, P, Q, (char, Encode<T, false, Fragment>)> {
+        UrlBuilder {
+            scheme: self.scheme,
+            userinfo: self.userinfo,
+            host: self.host,
+            port: self.port,
+            path: self.path,
+            query: self.query,
+            has_query: self.has_query,
+            fragment: ('#', Encode::new(fragment).set_encode_set::<Fragment>()),
+        }
+    }
+}
+
+impl<H, P, Q, F> UrlBuilder<'_, H, P, Q, F>
+where
+    H: StringT,
+    P: StringT,
+    Q: StringT,
+    F: StringT,
+{
+    #[cfg(feature = "feat-string-ext-http")]
+    /// Build an [`http::Uri`] directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`http::uri::InvalidUri`] if the result is not a valid URI,
+    /// e.g. the scheme or host is invalid.
+    pub fn to_uri(self) -> Result<http::Uri, http::uri::InvalidUri> {
+        let mut bytes = bytes::BytesMut::new();
+        self.encode_to_buf(&mut bytes);
+
+        http::Uri::from_maybe_shared(bytes.freeze())
+    }
+}
+
+impl<H, P, Q, F> StringT for UrlBuilder<'_, H, P, Q, F>
+where
+    H: StringT,
+    P: StringT,
+    Q: StringT,
+    F: StringT,
+{
+    fn encode_to_buf<B: BufT>(self, string: &mut B) {
+        string.extend_from_slice(self.scheme.as_bytes());
+        string.extend_from_slice(b"://");
+
+        if let Some((user, password)) = self.userinfo {
+            Encode::new(user)
+                .set_encode_set::<User>()
+                .encode_to_buf(string);
+
+            if let Some(password) = password {
+                string.push(b':');
+                Encode::new(password)
+                    .set_encode_set::<Userinfo>()
+                    .encode_to_buf(string);
+            }
+
+            string.push(b'@');
+        }
+
+        self.host.encode_to_buf(string);
+
+        if let Some(port) = self.port {
+            string.push(b':');
+            port.encode_to_buf(string);
+        }
+
+        self.path.encode_to_buf(string);
+        self.query.encode_to_buf(string);
+        self.fragment.encode_to_buf(string);
+    }
+}
+
+impl<H, P, Q, F> StringExtT for UrlBuilder<'_, H, P, Q, F>
+where
+    H: StringT,
+    P: StringT,
+    Q: StringT,
+    F: StringT,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::string::PushAnyT;
+
+    #[test]
+    fn test_url_builder() {
+        assert_eq!(
+            UrlBuilder::new("http", "localhost").to_string_ext(),
+            "http://localhost"
+        );
+        assert_eq!(
+            UrlBuilder::new("http", "[::1]")
+                .port(80)
+                .segment("")
+                .to_string_ext(),
+            "http://[::1]:80/"
+        );
+        assert_eq!(
+            UrlBuilder::new("ftp", ("files.", "example.com"))
+                .userinfo("a:b@c", Some("p:w@d"))
+                .segments(vec!["你好".to_string(), "x?y#z".to_string()])
+                .query_opt("skip", None::<&str>)
+                .query("k=", "")
+                .query("k=", "a+b c")
+                .to_string_ext(),
+            "ftp://a%3Ab%40c:p:w%40d@files.example.com/%E4%BD%A0%E5%A5%BD/x%3Fy%23z?k%3D=&k%3D=a%2Bb%20c"
+        );
+
+        // Existing content is untouched.
+        let mut string = "url: ".to_string();
+        string.push_any(UrlBuilder::new("https", "a.com").fragment("x y"));
+        assert_eq!(string, "url: https://a.com#x%20y");
+    }
+
+    #[cfg(feature = "feat-string-ext-http")]
+    #[test]
+    fn test_url_builder_uri() {
+        let uri = UrlBuilder::new("https", "example.com")
+            .segment("a b")
+            .segment(1u8)
+            .query("q", "你")
+            .to_uri()
+            .unwrap();
+
+        assert_eq!(uri.scheme_str(), Some("https"));
+        assert_eq!(uri.host(), Some("example.com"));
+        assert_eq!(uri.path(), "/a%20b/1");
+        assert_eq!(uri.query(), Some("q=%E4%BD%A0"));
+
+        let _ = UrlBuilder::new("https", "exa mple.com")
+            .to_uri()
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,230 @@
+//! Adapters for streaming string-like types into [`fmt::Write`] or
+//! [`io::Write`], without materializing the whole [`String`] first.
+//!
+//! See [`StringT::write_to_fmt`], [`StringT::write_to_io`] and
+//! [`DisplayWrapper`].
+
+use std::{fmt, io};
+
+use super::{len_hint_reserve, BufT, StringT};
+use crate::wrapper;
+
+/// Bytes buffered will be flushed to the underlying writer once reaching this
+/// size (at item boundaries, see [`BufT::commit`]).
+const CHUNK_SIZE: usize = 4096;
+
+#[inline]
+/// Encode the value chunk by chunk, `flush` is called with each chunk
+/// (complete items only, so always valid UTF-8).
+fn encode_chunked<T, E>(value: T, mut flush: impl FnMut(&[u8]) -> Result<(), E>) -> Result<(), E>
+where
+    T: StringT,
+{
+    let mut buf = ChunkBuf {
+        buf: Vec::with_capacity(len_hint_reserve(value.len_hint()).min(CHUNK_SIZE * 2)),
+        flush: &mut flush,
+        result: Ok(()),
+    };
+
+    value.encode_to_buf(&mut buf);
+
+    buf.flush();
+    buf.result
+}
+
+/// The [`BufT`] flushing bytes written to `F` on [`BufT::commit`].
+struct ChunkBuf<'f, F, E> {
+    buf: Vec<u8>,
+    flush: &'f mut F,
+    result: Result<(), E>,
+}
+
+impl<F, E> ChunkBuf<'_, F, E>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    #[inline]
+    fn flush(&mut self) {
+        if self.result.is_ok() && !self.buf.is_empty() {
+            self.result = (self.flush)(&self.buf);
+        }
+
+        // Once failed, just discard all bytes written later.
+        self.buf.clear();
+    }
+}
+
+impl<F, E> BufT for ChunkBuf<'_, F, E>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.buf.reserve(additional);
+    }
+
+    #[inline]
+    fn push(&mut self, byte: u8) {
+        self.buf.push(byte);
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    fn resize(&mut self, new_len: usize, value: u8) {
+        self.buf.resize(new_len, value);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.buf.truncate(len);
+    }
+
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self.buf.as_mut_slice()
+    }
+
+    #[inline]
+    fn commit(&mut self) {
+        if self.buf.len() >= CHUNK_SIZE {
+            self.flush();
+        }
+    }
+}
+
+#[inline]
+/// See [`StringT::write_to_fmt`].
+pub(crate) fn write_to_fmt<T, W>(value: T, writer: &mut W) -> fmt::Result
+where
+    T: StringT,
+    W: fmt::Write + ?Sized,
+{
+    encode_chunked(value, |chunk| {
+        // safe because of the `StringT` trait, and chunks are made up of
+        // complete items.
+        #[allow(unsafe_code)]
+        writer.write_str(unsafe { std::str::from_utf8_unchecked(chunk) })
+    })
+}
+
+#[inline]
+/// See [`StringT::write_to_io`].
+pub(crate) fn write_to_io<T, W>(value: T, writer: &mut W) -> io::Result<()>
+where
+    T: StringT,
+    W: io::Write + ?Sized,
+{
+    encode_chunked(value, |chunk| writer.write_all(chunk))
+}
+
+wrapper! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// Wrapper implementing [`Display`](fmt::Display) for any string-like
+    /// type, so that it can be used with `format!`, `write!` or `tracing`
+    /// fields directly.
+    ///
+    /// Since [`StringT`] consumes the value, the inner value is cloned each
+    /// time it is formatted (wrap references like `&String` to make it
+    /// cheap). Formatting options like width are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use macro_toolset::string::{NumStr, StringExtT};
+    /// let value = ("id=", NumStr::hex_default(255u8)).display();
+    /// assert_eq!(format!("[{value}]"), "[id=ff]");
+    /// ```
+    pub DisplayWrapper<T>(pub T)
+}
+
+impl<T> fmt::Display for DisplayWrapper<T>
+where
+    T: StringT + Clone,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.clone().write_to_fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::string::{
+        general::{iterator::IterWrapper, tuple::SeplessTuple},
+        StringExtT,
+    };
+
+    #[test]
+    fn test_write_to_fmt() {
+        let mut string = String::new();
+        ("a", 1u8, ',', "b").write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, "a1,b");
+
+        // Across chunks.
+        let iter = IterWrapper {
+            inner: (0..10000u32).map(|n| (n, ',')),
+        };
+        let mut string = String::new();
+        iter.clone().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, iter.to_string_ext());
+    }
+
+    #[test]
+    fn test_write_to_fmt_separator() {
+        // Items committing internally while the separator is pending.
+        let value = (
+            "x".repeat(CHUNK_SIZE - 1),
+            SeplessTuple {
+                inner: (None::<&str>, None::<&str>),
+            },
+            "y",
+        )
+            .joined(",");
+        let mut string = String::new();
+        value.clone().write_to_fmt(&mut string).unwrap();
+        assert_eq!(string, value.to_string_ext());
+    }
+
+    #[test]
+    fn test_write_to_io() {
+        let data = (0..10000u64).map(|n| n * 12345).collect::<Vec<_>>();
+
+        let mut bytes = Vec::new();
+        (&data[..]).write_to_io(&mut bytes).unwrap();
+        assert_eq!(bytes, (&data[..]).to_string_ext().into_bytes());
+
+        struct Failing(usize);
+
+        impl io::Write for Failing {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                self.0 += 1;
+                Err(io::Error::other("failing"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut failing = Failing(0);
+        assert!((&data[..]).write_to_io(&mut failing).is_err());
+        assert_eq!(failing.0, 1);
+    }
+
+    #[test]
+    fn test_display() {
+        let value = ("hello", ' ', 42i32).display();
+        assert_eq!(format!("{value}!"), "hello 42!");
+        assert_eq!(value.to_string(), "hello 42");
+    }
+}